@@ -0,0 +1,137 @@
+use soroban_sdk::{symbol_short, Address, Env, Symbol, Vec};
+
+pub const ROLE_ADMIN: Symbol = symbol_short!("ADMIN");
+
+/// Role storage and the two-step admin handover, shared by both contracts.
+pub struct Admins;
+
+impl Admins {
+    const ADMIN_CNT: Symbol = symbol_short!("ADMIN_CNT");
+    const PENDING_ADM: Symbol = symbol_short!("PEND_ADM");
+    const RENOUNCE: Symbol = symbol_short!("RENOUNCE");
+
+    pub fn init(env: &Env, admin: &Address) {
+        env.storage().instance().set(&Self::ADMIN_CNT, &0u32);
+        Self::grant_role(env, admin, ROLE_ADMIN);
+    }
+
+    pub fn has_role(env: &Env, account: &Address, role: Symbol) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(role, account.clone()))
+            .unwrap_or(false)
+    }
+
+    pub fn require_role(env: &Env, account: &Address, role: Symbol) {
+        if !Self::has_role(env, account, role) {
+            panic!("Missing required role");
+        }
+    }
+
+    pub fn grant_role(env: &Env, account: &Address, role: Symbol) {
+        if role == ROLE_ADMIN && !Self::has_role(env, account, ROLE_ADMIN) {
+            let count = Self::require_admin_count(env);
+            env.storage().instance().set(&Self::ADMIN_CNT, &(count + 1));
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(role, account.clone()), &true);
+    }
+
+    /// Revoking an admin also drops any handover they proposed, so their
+    /// nominee cannot accept it afterwards.
+    pub fn revoke_role(env: &Env, account: &Address, role: Symbol) {
+        if role == ROLE_ADMIN && Self::has_role(env, account, ROLE_ADMIN) {
+            let count = Self::require_admin_count(env);
+            if count <= 1 {
+                panic!("Cannot remove the last admin");
+            }
+            env.storage().instance().set(&Self::ADMIN_CNT, &(count - 1));
+
+            if let Some((proposer, _)) = Self::get_pending_admin(env) {
+                if proposer == *account {
+                    env.storage().instance().remove(&Self::PENDING_ADM);
+                }
+            }
+        }
+
+        env.storage().persistent().remove(&(role, account.clone()));
+    }
+
+    pub fn admin_count(env: &Env) -> u32 {
+        env.storage().instance().get(&Self::ADMIN_CNT).unwrap_or(0)
+    }
+
+    fn require_admin_count(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&Self::ADMIN_CNT)
+            .unwrap_or_else(|| panic!("Admin count not initialized"))
+    }
+
+    /// Contracts deployed before the count existed have none stored. An admin
+    /// records it once by listing the current admins; leaving one out only
+    /// makes the last-admin check stricter.
+    pub fn sync_admin_count(env: &Env, account: &Address, admins: &Vec<Address>) {
+        Self::require_role(env, account, ROLE_ADMIN);
+        if env.storage().instance().has(&Self::ADMIN_CNT) {
+            panic!("Admin count already initialized");
+        }
+        if !admins.contains(account) {
+            panic!("Caller must be listed");
+        }
+        for (i, admin) in admins.iter().enumerate() {
+            if !Self::has_role(env, &admin, ROLE_ADMIN) {
+                panic!("Account is not an admin");
+            }
+            if admins.first_index_of(&admin) != Some(i as u32) {
+                panic!("Duplicate admin");
+            }
+        }
+
+        env.storage().instance().set(&Self::ADMIN_CNT, &admins.len());
+    }
+
+    pub fn propose_admin(env: &Env, account: &Address, new_admin: &Address) {
+        Self::require_role(env, account, ROLE_ADMIN);
+        if account == new_admin {
+            panic!("Already admin");
+        }
+        env.storage()
+            .instance()
+            .set(&Self::PENDING_ADM, &(account.clone(), new_admin.clone()));
+    }
+
+    pub fn get_pending_admin(env: &Env) -> Option<(Address, Address)> {
+        env.storage().instance().get(&Self::PENDING_ADM)
+    }
+
+    pub fn cancel_admin_transfer(env: &Env, account: &Address) {
+        Self::require_role(env, account, ROLE_ADMIN);
+        env.storage().instance().remove(&Self::PENDING_ADM);
+    }
+
+    pub fn accept_admin(env: &Env, account: &Address) -> Address {
+        let (proposer, new_admin) = Self::get_pending_admin(env)
+            .unwrap_or_else(|| panic!("No pending admin transfer"));
+        if *account != new_admin {
+            panic!("Not the proposed admin");
+        }
+
+        env.storage().instance().remove(&Self::PENDING_ADM);
+        Self::grant_role(env, account, ROLE_ADMIN);
+        if Self::has_role(env, &proposer, ROLE_ADMIN) {
+            Self::revoke_role(env, &proposer, ROLE_ADMIN);
+        }
+        proposer
+    }
+
+    pub fn renounce_admin(env: &Env, account: &Address, confirmation: Symbol) {
+        if confirmation != Self::RENOUNCE {
+            panic!("Renounce not confirmed");
+        }
+        Self::require_role(env, account, ROLE_ADMIN);
+        Self::revoke_role(env, account, ROLE_ADMIN);
+    }
+}
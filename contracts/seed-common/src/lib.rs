@@ -0,0 +1,7 @@
+#![no_std]
+
+pub mod admin;
+pub mod seed_data;
+
+#[cfg(test)]
+mod test;
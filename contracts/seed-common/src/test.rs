@@ -0,0 +1,147 @@
+#![cfg(test)]
+use soroban_sdk::{
+    contract, contractimpl, symbol_short, testutils::Address as _, Address, Env, Symbol, Vec,
+};
+use crate::admin::{Admins, ROLE_ADMIN};
+
+/// Exposes the shared admin logic the way both contracts wire it up.
+#[contract]
+pub struct AdminHost;
+
+#[contractimpl]
+impl AdminHost {
+    pub fn init(env: Env, admin: Address) {
+        Admins::init(&env, &admin);
+    }
+    
+    pub fn grant_role(env: Env, account: Address, role: Symbol) {
+        Admins::grant_role(&env, &account, role);
+    }
+    
+    pub fn revoke_role(env: Env, account: Address, role: Symbol) {
+        Admins::revoke_role(&env, &account, role);
+    }
+    
+    pub fn has_role(env: Env, account: Address, role: Symbol) -> bool {
+        Admins::has_role(&env, &account, role)
+    }
+    
+    pub fn get_admin_count(env: Env) -> u32 {
+        Admins::admin_count(&env)
+    }
+    
+    pub fn sync_admin_count(env: Env, caller: Address, admins: Vec<Address>) {
+        Admins::sync_admin_count(&env, &caller, &admins);
+    }
+    
+    pub fn propose_admin(env: Env, caller: Address, new_admin: Address) {
+        Admins::propose_admin(&env, &caller, &new_admin);
+    }
+    
+    pub fn accept_admin(env: Env, new_admin: Address) -> Address {
+        Admins::accept_admin(&env, &new_admin)
+    }
+    
+    pub fn cancel_admin_transfer(env: Env, caller: Address) {
+        Admins::cancel_admin_transfer(&env, &caller);
+    }
+    
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        Admins::get_pending_admin(&env).map(|(_, new_admin)| new_admin)
+    }
+    
+    pub fn renounce_admin(env: Env, caller: Address, confirmation: Symbol) {
+        Admins::renounce_admin(&env, &caller, confirmation);
+    }
+}
+
+fn setup() -> (Env, AdminHostClient<'static>, Address) {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    
+    let contract_id = env.register(AdminHost, ());
+    let client = AdminHostClient::new(&env, &contract_id);
+    
+    client.init(&admin);
+    (env, client, admin)
+}
+
+#[test]
+fn test_admin_transfer_and_renounce() {
+    let (env, client, admin) = setup();
+    let successor = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    
+    assert_eq!(client.get_admin_count(), 1);
+    
+    assert!(client.try_revoke_role(&admin, &ROLE_ADMIN).is_err());
+    assert!(client.try_renounce_admin(&admin, &symbol_short!("RENOUNCE")).is_err());
+    
+    client.propose_admin(&admin, &successor);
+    assert_eq!(client.get_pending_admin(), Some(successor.clone()));
+    assert!(!client.has_role(&successor, &ROLE_ADMIN));
+    assert!(client.try_accept_admin(&stranger).is_err());
+    
+    assert_eq!(client.accept_admin(&successor), admin);
+    assert!(client.has_role(&successor, &ROLE_ADMIN));
+    assert!(!client.has_role(&admin, &ROLE_ADMIN));
+    assert_eq!(client.get_pending_admin(), None);
+    assert_eq!(client.get_admin_count(), 1);
+    
+    client.grant_role(&stranger, &ROLE_ADMIN);
+    assert_eq!(client.get_admin_count(), 2);
+    assert!(client.try_renounce_admin(&stranger, &symbol_short!("YES")).is_err());
+    client.renounce_admin(&stranger, &symbol_short!("RENOUNCE"));
+    assert!(!client.has_role(&stranger, &ROLE_ADMIN));
+    assert_eq!(client.get_admin_count(), 1);
+}
+
+#[test]
+fn test_revoking_proposer_cancels_handover() {
+    let (env, client, admin) = setup();
+    let rogue = Address::generate(&env);
+    let nominee = Address::generate(&env);
+    let other = Address::generate(&env);
+    
+    client.grant_role(&rogue, &ROLE_ADMIN);
+    client.propose_admin(&rogue, &nominee);
+    client.revoke_role(&rogue, &ROLE_ADMIN);
+    assert_eq!(client.get_pending_admin(), None);
+    assert!(client.try_accept_admin(&nominee).is_err());
+    assert!(!client.has_role(&nominee, &ROLE_ADMIN));
+    
+    client.grant_role(&other, &ROLE_ADMIN);
+    client.propose_admin(&admin, &nominee);
+    client.revoke_role(&other, &ROLE_ADMIN);
+    assert_eq!(client.get_pending_admin(), Some(nominee));
+}
+
+#[test]
+fn test_sync_admin_count_on_existing_deployment() {
+    let (env, client, admin) = setup();
+    let second = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    client.grant_role(&second, &ROLE_ADMIN);
+    env.as_contract(&client.address, || {
+        env.storage().instance().remove(&symbol_short!("ADMIN_CNT"));
+    });
+    
+    assert_eq!(client.get_admin_count(), 0);
+    assert!(client.try_revoke_role(&second, &ROLE_ADMIN).is_err());
+    assert!(client.try_grant_role(&stranger, &ROLE_ADMIN).is_err());
+    
+    let both = Vec::from_array(&env, [admin.clone(), second.clone()]);
+    assert!(client.try_sync_admin_count(&stranger, &both).is_err());
+    assert!(client
+        .try_sync_admin_count(&admin, &Vec::from_array(&env, [admin.clone(), stranger.clone()]))
+        .is_err());
+    assert!(client
+        .try_sync_admin_count(&admin, &Vec::from_array(&env, [admin.clone(), admin.clone()]))
+        .is_err());
+    client.sync_admin_count(&admin, &both);
+    assert_eq!(client.get_admin_count(), 2);
+    assert!(client.try_sync_admin_count(&admin, &both).is_err());
+    
+    client.revoke_role(&second, &ROLE_ADMIN);
+    assert_eq!(client.get_admin_count(), 1);
+}
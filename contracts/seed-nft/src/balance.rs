@@ -0,0 +1,75 @@
+use soroban_sdk::{contracttype, symbol_short, Env, Symbol};
+use crate::metadata::QuantityUnit;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct MassBalance {
+    pub unit: QuantityUnit,
+    pub initial: u64,
+    pub split: u64,
+    pub merged: u64,
+    pub sold: u64,
+    pub wasted: u64,
+    pub loss: u64,
+    pub remaining: u64,
+}
+
+pub enum Outflow {
+    Split,
+    Merged,
+    Sold,
+    Wasted,
+    Loss,
+}
+
+pub struct Balances;
+
+impl Balances {
+    const BALANCE: Symbol = symbol_short!("BALANCE");
+
+    pub fn open(env: &Env, token_id: &u128, unit: QuantityUnit, quantity: u64) -> MassBalance {
+        let balance = MassBalance {
+            unit,
+            initial: quantity,
+            split: 0,
+            merged: 0,
+            sold: 0,
+            wasted: 0,
+            loss: 0,
+            remaining: quantity,
+        };
+        env.storage()
+            .persistent()
+            .set(&(Self::BALANCE, token_id), &balance);
+        balance
+    }
+
+    pub fn get(env: &Env, token_id: &u128) -> Option<MassBalance> {
+        env.storage()
+            .persistent()
+            .get(&(Self::BALANCE, token_id))
+    }
+
+    pub fn debit(env: &Env, token_id: &u128, outflow: Outflow, amount: u64) -> MassBalance {
+        let mut balance = Self::get(env, token_id)
+            .unwrap_or_else(|| panic!("Quantity not recorded"));
+
+        if amount > balance.remaining {
+            panic!("Outputs exceed available quantity");
+        }
+
+        match outflow {
+            Outflow::Split => balance.split += amount,
+            Outflow::Merged => balance.merged += amount,
+            Outflow::Sold => balance.sold += amount,
+            Outflow::Wasted => balance.wasted += amount,
+            Outflow::Loss => balance.loss += amount,
+        }
+        balance.remaining -= amount;
+
+        env.storage()
+            .persistent()
+            .set(&(Self::BALANCE, token_id), &balance);
+        balance
+    }
+}
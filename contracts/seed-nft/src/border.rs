@@ -0,0 +1,188 @@
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol};
+use crate::nft::SeedNFT;
+use crate::roles::{ROLE_ADMIN, ROLE_CUSTOMS};
+use crate::Error;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CrossingKind {
+    Export = 0,
+    Import = 1,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExportPermit {
+    pub permit_number: String,
+    pub issued_by: Address,
+    pub origin_country: String,
+    pub destination_country: String,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CustomsClearance {
+    pub authority: Address,
+    pub reference: String,
+    pub document_hash: BytesN<32>,
+    pub cleared_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct BorderCrossing {
+    pub kind: CrossingKind,
+    pub permit_number: String,
+    pub issued_by: Address,
+    pub origin_country: String,
+    pub destination_country: String,
+    pub clearance: CustomsClearance,
+    pub recorded_by: Address,
+    pub timestamp: u64,
+}
+
+pub struct Borders;
+
+impl Borders {
+    const HOME: Symbol = symbol_short!("HOME_CTRY");
+    const COUNTRY: Symbol = symbol_short!("COUNTRY");
+    const ACCT_CTRY: Symbol = symbol_short!("ACCT_CTRY");
+    const EXPORT: Symbol = symbol_short!("EXPORT");
+    const SHIPPED: Symbol = symbol_short!("SHIPPED");
+
+    pub fn set_home_country(env: &Env, caller: &Address, country: &String) {
+        SeedNFT::require_role(env, caller, ROLE_ADMIN);
+        env.storage().instance().set(&Self::HOME, country);
+    }
+
+    pub fn get_home_country(env: &Env) -> Option<String> {
+        env.storage().instance().get(&Self::HOME)
+    }
+
+    pub fn set_account_country(env: &Env, caller: &Address, account: &Address, country: &String) {
+        SeedNFT::require_regulator(env, caller);
+        env.storage()
+            .persistent()
+            .set(&(Self::ACCT_CTRY, account.clone()), country);
+    }
+
+    pub fn get_account_country(env: &Env, account: &Address) -> Option<String> {
+        env.storage()
+            .persistent()
+            .get(&(Self::ACCT_CTRY, account.clone()))
+    }
+
+    pub fn get_token_country(env: &Env, token_id: &u128) -> Option<String> {
+        env.storage()
+            .persistent()
+            .get(&(Self::COUNTRY, *token_id))
+            .or_else(|| Self::get_home_country(env))
+    }
+
+    /// Tokens minted from another one start where their source is, so clones,
+    /// lots and products of an imported token keep its jurisdiction.
+    pub fn inherit_country(env: &Env, source: &u128, token_id: &u128) {
+        let country: Option<String> = env
+            .storage()
+            .persistent()
+            .get(&(Self::COUNTRY, *source));
+        if let Some(country) = country {
+            Self::set_token_country(env, token_id, &country);
+        }
+    }
+
+    pub fn set_token_country(env: &Env, token_id: &u128, country: &String) {
+        env.storage()
+            .persistent()
+            .set(&(Self::COUNTRY, *token_id), country);
+    }
+
+    pub fn set_pending_export(env: &Env, token_id: &u128, permit: &ExportPermit) {
+        env.storage()
+            .persistent()
+            .set(&(Self::EXPORT, *token_id), permit);
+    }
+
+    pub fn get_pending_export(env: &Env, token_id: &u128) -> Option<ExportPermit> {
+        env.storage()
+            .persistent()
+            .get(&(Self::EXPORT, *token_id))
+    }
+
+    pub fn clear_pending_export(env: &Env, token_id: &u128) {
+        env.storage()
+            .persistent()
+            .remove(&(Self::EXPORT, *token_id));
+        env.storage()
+            .persistent()
+            .remove(&(Self::SHIPPED, *token_id));
+    }
+
+    pub fn is_shipped(env: &Env, token_id: &u128) -> bool {
+        env.storage()
+            .persistent()
+            .has(&(Self::SHIPPED, *token_id))
+    }
+
+    /// Permits are countersigned by a regulator and clearances signed by an
+    /// account holding the customs role.
+    pub fn require_issuer(env: &Env, permit: &ExportPermit) {
+        permit.issued_by.require_auth();
+        SeedNFT::require_regulator(env, &permit.issued_by);
+    }
+
+    pub fn require_customs(env: &Env, clearance: &CustomsClearance) {
+        clearance.authority.require_auth();
+        if !SeedNFT::has_role(env, &clearance.authority, ROLE_CUSTOMS) {
+            panic!("Clearance authority is not customs");
+        }
+    }
+
+    /// Once border tracking is configured, a recipient without a recorded
+    /// country is rejected rather than assumed to be domestic.
+    fn foreign_destination(env: &Env, token_id: &u128, to: &Address) -> Result<Option<String>, Error> {
+        let origin = match Self::get_token_country(env, token_id) {
+            Some(country) => country,
+            None => return Ok(None),
+        };
+        let destination = Self::get_account_country(env, to).ok_or(Error::UnknownJurisdiction)?;
+        if destination == origin {
+            return Ok(None);
+        }
+        Ok(Some(destination))
+    }
+
+    /// Checks a transfer of `token_id` to `to` and uses up the token's export
+    /// permit when it carries the token abroad, so one permit covers a single
+    /// shipment.
+    pub fn authorize_transfer(env: &Env, token_id: &u128, to: &Address) -> Result<(), Error> {
+        let destination = match Self::foreign_destination(env, token_id, to)? {
+            Some(destination) => destination,
+            None => return Ok(()),
+        };
+
+        match Self::get_pending_export(env, token_id) {
+            Some(permit)
+                if permit.destination_country == destination
+                    && permit.expires_at > env.ledger().timestamp()
+                    && !Self::is_shipped(env, token_id) =>
+            {
+                env.storage()
+                    .persistent()
+                    .set(&(Self::SHIPPED, *token_id), to);
+                Ok(())
+            }
+            _ => Err(Error::MissingExportPermit),
+        }
+    }
+
+    /// Tokens minted from `source` cannot leave its jurisdiction; only the
+    /// source token itself can be exported.
+    pub fn check_domestic(env: &Env, source: &u128, to: &Address) -> Result<(), Error> {
+        match Self::foreign_destination(env, source, to)? {
+            Some(_) => Err(Error::MissingExportPermit),
+            None => Ok(()),
+        }
+    }
+}
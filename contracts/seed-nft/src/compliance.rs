@@ -0,0 +1,133 @@
+use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol, Vec};
+use crate::history::Sale;
+use crate::lab::{Analyte, AnalyteKind, LabResult, PanelVerdict};
+
+/// Highest tolerated value for an analyte. `analyte: None` covers every
+/// analyte of `kind` that has no limit of its own.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActionLimit {
+    pub kind: AnalyteKind,
+    pub analyte: Option<String>,
+    pub max: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ComplianceThresholds {
+    pub max_total_thc_bps: Option<u32>,
+    pub max_moisture_bps: Option<u32>,
+    pub action_limits: Vec<ActionLimit>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FreezeRecord {
+    pub reason: String,
+    pub frozen_by: Address,
+    pub frozen_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ComplianceRecord {
+    pub token_id: u128,
+    pub frozen_by: Option<Address>,
+    pub freeze_reason: Option<String>,
+    pub frozen_at: Option<u64>,
+    pub consumer: Option<Address>,
+    pub lab_results: Vec<LabResult>,
+    pub sales: Vec<Sale>,
+}
+
+pub struct Compliance;
+
+impl Compliance {
+    const THRESHOLD: Symbol = symbol_short!("THRESHOLD");
+    const FROZEN: Symbol = symbol_short!("FROZEN");
+    const DEFAULT_MAX_MOISTURE_BPS: u32 = 1500;
+
+    pub fn set_thresholds(env: &Env, thresholds: &ComplianceThresholds) {
+        env.storage().instance().set(&Self::THRESHOLD, thresholds);
+    }
+
+    pub fn get_thresholds(env: &Env) -> ComplianceThresholds {
+        env.storage()
+            .instance()
+            .get(&Self::THRESHOLD)
+            .unwrap_or(ComplianceThresholds {
+                max_total_thc_bps: None,
+                max_moisture_bps: Some(Self::DEFAULT_MAX_MOISTURE_BPS),
+                action_limits: Vec::new(env),
+            })
+    }
+
+    /// Records a verdict for potency, moisture and each analyte panel; the
+    /// result passes only if all of them do. Any detected analyte without a
+    /// configured limit fails its panel.
+    pub fn evaluate(env: &Env, result: &mut LabResult) {
+        let thresholds = Self::get_thresholds(env);
+
+        result.potency_passed = thresholds
+            .max_total_thc_bps
+            .is_none_or(|max_thc| result.total_thc_bps <= max_thc);
+        result.moisture_passed = thresholds
+            .max_moisture_bps
+            .is_none_or(|max_moisture| result.moisture_bps <= max_moisture);
+
+        let mut panels = Vec::new(env);
+        for kind in AnalyteKind::ALL {
+            let passed = result
+                .analytes
+                .iter()
+                .filter(|analyte| analyte.kind == kind)
+                .all(|analyte| analyte.value <= Self::limit_for(&thresholds.action_limits, &analyte));
+            panels.push_back(PanelVerdict { kind, passed });
+        }
+
+        result.passed = result.potency_passed
+            && result.moisture_passed
+            && panels.iter().all(|panel| panel.passed);
+        result.panels = panels;
+    }
+
+    fn limit_for(limits: &Vec<ActionLimit>, analyte: &Analyte) -> u32 {
+        let mut fallback = 0;
+        for limit in limits.iter() {
+            if limit.kind != analyte.kind {
+                continue;
+            }
+            match limit.analyte {
+                Some(name) if name == analyte.name => return limit.max,
+                Some(_) => {}
+                None => fallback = limit.max,
+            }
+        }
+        fallback
+    }
+
+    pub fn freeze(env: &Env, token_id: &u128, reason: String, frozen_by: &Address) {
+        let record = FreezeRecord {
+            reason,
+            frozen_by: frozen_by.clone(),
+            frozen_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .persistent()
+            .set(&(Self::FROZEN, token_id), &record);
+    }
+
+    pub fn unfreeze(env: &Env, token_id: &u128) {
+        env.storage().persistent().remove(&(Self::FROZEN, token_id));
+    }
+
+    pub fn get_freeze(env: &Env, token_id: &u128) -> Option<FreezeRecord> {
+        env.storage()
+            .persistent()
+            .get(&(Self::FROZEN, token_id))
+    }
+
+    pub fn is_frozen(env: &Env, token_id: &u128) -> bool {
+        env.storage().persistent().has(&(Self::FROZEN, token_id))
+    }
+}
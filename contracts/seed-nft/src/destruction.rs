@@ -0,0 +1,23 @@
+use soroban_sdk::{contracttype, Address, Vec};
+use crate::metadata::QuantityUnit;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DestructionMethod {
+    Composting = 0,
+    Incineration = 1,
+    Grinding = 2,
+    Landfill = 3,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Destruction {
+    pub quantity: u64,
+    pub unit: QuantityUnit,
+    pub method: DestructionMethod,
+    pub witnesses: Vec<Address>,
+    pub destroyed_by: Address,
+    pub timestamp: u64,
+    pub remaining: u64,
+}
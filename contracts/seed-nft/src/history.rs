@@ -1,5 +1,8 @@
 use soroban_sdk::{Address, String, Vec, contracttype, symbol_short};
+use crate::border::BorderCrossing;
+use crate::destruction::Destruction;
 use crate::lifecycle::LifecycleState;
+use crate::metadata::QuantityUnit;
 
 #[contracttype]
 #[derive(Clone)]
@@ -9,36 +12,197 @@ pub struct StateTransition {
     pub timestamp: u64,
     pub updated_by: Address,
     pub notes: Option<String>,
+    pub related_token: Option<u128>,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Origin {
+    Cloned = 0,
+    Split = 1,
+    Merged = 2,
+    Sold = 3,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Genesis {
+    pub origin: Origin,
+    pub state: LifecycleState,
+    pub sources: Vec<u128>,
+    pub created_by: Address,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Sale {
+    pub seller: Address,
+    pub consumer: Address,
+    pub quantity: u64,
+    pub unit: QuantityUnit,
+    pub timestamp: u64,
+    pub unit_token: Option<u128>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Custody {
+    pub from: Option<Address>,
+    pub to: Option<Address>,
+    pub operator: Address,
+    pub timestamp: u64,
+    pub manifest: Option<String>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum HistoryEntry {
+    Genesis(Genesis),
+    Transition(StateTransition),
+    Custody(Custody),
+    Sale(Sale),
+    Destruction(Destruction),
+    Crossing(BorderCrossing),
 }
 
 pub struct History;
 
 impl History {
+    pub fn add_genesis(
+        env: &soroban_sdk::Env,
+        token_id: &u128,
+        genesis: &Genesis,
+    ) {
+        Self::append(env, token_id, HistoryEntry::Genesis(genesis.clone()));
+    }
+
     pub fn add_transition(
         env: &soroban_sdk::Env,
         token_id: &u128,
         transition: &StateTransition,
     ) {
-        let key = (symbol_short!("HISTORY"), token_id);
-        let mut history: Vec<StateTransition> = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or_else(|| Vec::new(env));
-        
-        history.push_back(transition.clone());
-        env.storage().persistent().set(&key, &history);
+        Self::append(env, token_id, HistoryEntry::Transition(transition.clone()));
+    }
+
+    pub fn add_custody(
+        env: &soroban_sdk::Env,
+        token_id: &u128,
+        custody: &Custody,
+    ) {
+        Self::append(env, token_id, HistoryEntry::Custody(custody.clone()));
+    }
+
+    pub fn add_sale(
+        env: &soroban_sdk::Env,
+        token_id: &u128,
+        sale: &Sale,
+    ) {
+        Self::append(env, token_id, HistoryEntry::Sale(sale.clone()));
+    }
+
+    pub fn add_destruction(
+        env: &soroban_sdk::Env,
+        token_id: &u128,
+        destruction: &Destruction,
+    ) {
+        Self::append(env, token_id, HistoryEntry::Destruction(destruction.clone()));
+    }
+
+    pub fn add_crossing(
+        env: &soroban_sdk::Env,
+        token_id: &u128,
+        crossing: &BorderCrossing,
+    ) {
+        Self::append(env, token_id, HistoryEntry::Crossing(crossing.clone()));
     }
 
     pub fn get_history(
         env: &soroban_sdk::Env,
         token_id: &u128,
-    ) -> Vec<StateTransition> {
+    ) -> Vec<HistoryEntry> {
         let key = (symbol_short!("HISTORY"), token_id);
         env.storage()
             .persistent()
             .get(&key)
             .unwrap_or_else(|| Vec::new(env))
     }
-}
 
+    /// History without sale entries, whose consumers are only shown to
+    /// regulators.
+    pub fn get_public_history(
+        env: &soroban_sdk::Env,
+        token_id: &u128,
+    ) -> Vec<HistoryEntry> {
+        let mut history = Vec::new(env);
+        for entry in Self::get_history(env, token_id).iter() {
+            if !matches!(entry, HistoryEntry::Sale(_)) {
+                history.push_back(entry);
+            }
+        }
+        history
+    }
+
+    pub fn get_sales(
+        env: &soroban_sdk::Env,
+        token_id: &u128,
+    ) -> Vec<Sale> {
+        let mut sales = Vec::new(env);
+        for entry in Self::get_history(env, token_id).iter() {
+            if let HistoryEntry::Sale(sale) = entry {
+                sales.push_back(sale);
+            }
+        }
+        sales
+    }
+
+    pub fn get_custody(
+        env: &soroban_sdk::Env,
+        token_id: &u128,
+    ) -> Vec<Custody> {
+        let mut custody = Vec::new(env);
+        for entry in Self::get_history(env, token_id).iter() {
+            if let HistoryEntry::Custody(record) = entry {
+                custody.push_back(record);
+            }
+        }
+        custody
+    }
+
+    pub fn get_destructions(
+        env: &soroban_sdk::Env,
+        token_id: &u128,
+    ) -> Vec<Destruction> {
+        let mut destructions = Vec::new(env);
+        for entry in Self::get_history(env, token_id).iter() {
+            if let HistoryEntry::Destruction(destruction) = entry {
+                destructions.push_back(destruction);
+            }
+        }
+        destructions
+    }
+
+    pub fn get_crossings(
+        env: &soroban_sdk::Env,
+        token_id: &u128,
+    ) -> Vec<BorderCrossing> {
+        let mut crossings = Vec::new(env);
+        for entry in Self::get_history(env, token_id).iter() {
+            if let HistoryEntry::Crossing(crossing) = entry {
+                crossings.push_back(crossing);
+            }
+        }
+        crossings
+    }
+
+    fn append(
+        env: &soroban_sdk::Env,
+        token_id: &u128,
+        entry: HistoryEntry,
+    ) {
+        let key = (symbol_short!("HISTORY"), token_id);
+        let mut history = Self::get_history(env, token_id);
+        history.push_back(entry);
+        env.storage().persistent().set(&key, &history);
+    }
+}
@@ -0,0 +1,158 @@
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol, Vec};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Terpene {
+    pub name: String,
+    pub bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AnalyteKind {
+    Pesticide = 0,
+    HeavyMetal = 1,
+    Microbial = 2,
+    Mycotoxin = 3,
+    Solvent = 4,
+}
+
+impl AnalyteKind {
+    pub const ALL: [AnalyteKind; 5] = [
+        AnalyteKind::Pesticide,
+        AnalyteKind::HeavyMetal,
+        AnalyteKind::Microbial,
+        AnalyteKind::Mycotoxin,
+        AnalyteKind::Solvent,
+    ];
+}
+
+/// Whether every analyte of one panel stayed within its action limit.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PanelVerdict {
+    pub kind: AnalyteKind,
+    pub passed: bool,
+}
+
+/// A measured contaminant, in ppb for chemical analytes and CFU/g for microbials.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Analyte {
+    pub kind: AnalyteKind,
+    pub name: String,
+    pub value: u32,
+}
+
+/// What a lab submits; the submitting lab and the verdict are filled in on-chain.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LabSubmission {
+    pub sampled_at: u64,
+    pub thc_bps: u32,
+    pub thca_bps: u32,
+    pub cbd_bps: u32,
+    pub terpenes: Vec<Terpene>,
+    pub analytes: Vec<Analyte>,
+    pub moisture_bps: u32,
+    pub certificate_hash: BytesN<32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LabResult {
+    pub lab: Address,
+    pub sampled_at: u64,
+    pub thc_bps: u32,
+    pub thca_bps: u32,
+    pub total_thc_bps: u32,
+    pub cbd_bps: u32,
+    pub terpenes: Vec<Terpene>,
+    pub analytes: Vec<Analyte>,
+    pub moisture_bps: u32,
+    pub certificate_hash: BytesN<32>,
+    pub potency_passed: bool,
+    pub moisture_passed: bool,
+    pub panels: Vec<PanelVerdict>,
+    pub passed: bool,
+}
+
+impl LabResult {
+    /// THCA decarboxylates to THC at 87.7% of its mass.
+    const THCA_TO_THC_PER_MILLE: u64 = 877;
+
+    pub fn total_thc_bps(thc_bps: u32, thca_bps: u32) -> u32 {
+        let converted = thca_bps as u64 * Self::THCA_TO_THC_PER_MILLE / 1000;
+        thc_bps.saturating_add(converted as u32)
+    }
+
+    pub fn from_submission(env: &Env, lab: &Address, submission: LabSubmission) -> LabResult {
+        LabResult {
+            lab: lab.clone(),
+            sampled_at: submission.sampled_at,
+            thc_bps: submission.thc_bps,
+            thca_bps: submission.thca_bps,
+            total_thc_bps: Self::total_thc_bps(submission.thc_bps, submission.thca_bps),
+            cbd_bps: submission.cbd_bps,
+            terpenes: submission.terpenes,
+            analytes: submission.analytes,
+            moisture_bps: submission.moisture_bps,
+            certificate_hash: submission.certificate_hash,
+            potency_passed: false,
+            moisture_passed: false,
+            panels: Vec::new(env),
+            passed: false,
+        }
+    }
+}
+
+pub struct Accreditations;
+
+impl Accreditations {
+    const ACCREDIT: Symbol = symbol_short!("ACCREDIT");
+
+    pub fn set(env: &Env, lab: &Address, expires_at: u64) {
+        env.storage()
+            .persistent()
+            .set(&(Self::ACCREDIT, lab.clone()), &expires_at);
+    }
+
+    pub fn get(env: &Env, lab: &Address) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&(Self::ACCREDIT, lab.clone()))
+    }
+
+    pub fn require_accredited(env: &Env, lab: &Address) {
+        match Self::get(env, lab) {
+            Some(expires_at) if expires_at > env.ledger().timestamp() => {}
+            Some(_) => panic!("Lab accreditation expired"),
+            None => panic!("Lab not accredited"),
+        }
+    }
+}
+
+pub struct LabResults;
+
+impl LabResults {
+    const LAB_RES: Symbol = symbol_short!("LAB_RES");
+
+    pub fn add(env: &Env, token_id: &u128, result: &LabResult) {
+        let mut results = Self::get(env, token_id);
+        results.push_back(result.clone());
+        env.storage()
+            .persistent()
+            .set(&(Self::LAB_RES, token_id), &results);
+    }
+
+    pub fn get(env: &Env, token_id: &u128) -> Vec<LabResult> {
+        env.storage()
+            .persistent()
+            .get(&(Self::LAB_RES, token_id))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    pub fn latest(env: &Env, token_id: &u128) -> Option<LabResult> {
+        Self::get(env, token_id).last()
+    }
+}
@@ -1,20 +1,47 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracterror, contractevent, symbol_short,
-    Address, Env, String, Symbol, Vec,
+    contract, contractimpl, contracterror, contractevent, contracttype, panic_with_error,
+    symbol_short, vec, Address, Env, String, Symbol, Vec,
 };
 use stellar_tokens::non_fungible::{NonFungibleToken, Base};
 use stellar_macros::default_impl;
+use seed_common::admin::Admins;
 use crate::nft::SeedNFT;
-use crate::metadata::{SeedMetadata, Attribute, OpenSeaMetadata};
-use crate::lifecycle::LifecycleState;
-use crate::history::{History, StateTransition};
-use crate::roles::{ROLE_ADMIN, ROLE_CULTIVATOR, ROLE_PROCESSOR, ROLE_DISPENSARY};
+use crate::metadata::{SeedMetadata, Attribute, OpenSeaMetadata, LotSpec, ProductSpec, QuantityUnit};
+use crate::balance::{Balances, MassBalance, Outflow};
+use crate::border::{BorderCrossing, Borders, CrossingKind, CustomsClearance, ExportPermit};
+use crate::destruction::{Destruction, DestructionMethod};
+use crate::lifecycle::{ConsumedPolicy, LifecycleState};
+use crate::history::{Custody, Genesis, History, HistoryEntry, Origin, StateTransition, Sale};
+use crate::lineage::Lineage;
+use crate::lab::{Accreditations, LabResult, LabResults, LabSubmission};
+use crate::compliance::{Compliance, ComplianceRecord, ComplianceThresholds, FreezeRecord};
+use crate::recall::{Recall, RecallScope, Recalls};
+use crate::license::{License, LicenseStatus, LicenseType, Licenses};
+use crate::purchases::{Allowance, PurchaseLimit, Purchases};
+use crate::provenance::{Provenance, Provenances};
+use crate::whitelist::{Attestation, WhitelistCategory, WhitelistEntry};
+use crate::requirements::{Requirements, StateRequirements};
+use crate::transfers::{TransferRule, TransferRules};
+use crate::roles::{ROLE_ADMIN, ROLE_CULTIVATOR, ROLE_PROCESSOR, ROLE_DISPENSARY, ROLE_LAB};
 
 mod nft;
 mod metadata;
 mod lifecycle;
 mod history;
+mod lineage;
+mod balance;
+mod border;
+mod destruction;
+mod requirements;
+mod transfers;
+mod lab;
+mod compliance;
+mod recall;
+mod license;
+mod purchases;
+mod provenance;
+mod whitelist;
 mod roles;
 
 #[contracterror]
@@ -25,6 +52,26 @@ pub enum Error {
     NotWhitelisted = 3,
     Paused = 4,
     TokenNotFound = 5,
+    MissingLocation = 6,
+    MissingQuantity = 7,
+    MissingLabAnalysis = 8,
+    UnlicensedDestination = 9,
+    ProductExpired = 10,
+    FailedLabAnalysis = 11,
+    TokenFrozen = 12,
+    PurchaseLimitExceeded = 13,
+    MissingAttestation = 14,
+    RecipientNotAllowed = 15,
+    MissingExportPermit = 16,
+    UnknownJurisdiction = 17,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct BatchOutcome {
+    pub token_id: u128,
+    pub success: bool,
+    pub error: Option<u32>,
 }
 
 #[contractevent]
@@ -33,6 +80,63 @@ pub struct MintEvent {
     pub token_id: u128,
 }
 
+#[contractevent]
+pub struct CloneEvent {
+    pub parent_token_id: u128,
+    pub token_id: u128,
+    pub to: Address,
+}
+
+#[contractevent]
+pub struct SplitEvent {
+    pub parent_token_id: u128,
+    pub token_id: u128,
+    pub product_type: String,
+}
+
+#[contractevent]
+pub struct MergeEvent {
+    pub token_id: u128,
+    pub inputs: Vec<u128>,
+    pub to: Address,
+}
+
+#[contractevent]
+pub struct QuantityRecordedEvent {
+    pub token_id: u128,
+    pub quantity: u64,
+    pub unit: QuantityUnit,
+}
+
+#[contractevent]
+pub struct SaleEvent {
+    pub token_id: u128,
+    pub quantity: u64,
+    pub remaining: u64,
+}
+
+#[contractevent]
+pub struct DestructionEvent {
+    pub token_id: u128,
+    pub quantity: u64,
+    pub method: DestructionMethod,
+    pub destroyed_by: Address,
+}
+
+#[contractevent]
+pub struct BorderCrossingEvent {
+    pub token_id: u128,
+    pub kind: CrossingKind,
+    pub permit_number: String,
+    pub destination_country: String,
+}
+
+#[contractevent]
+pub struct BurnEvent {
+    pub token_id: u128,
+    pub owner: Address,
+}
+
 #[contractevent]
 pub struct StateTransitionEvent {
     pub token_id: u128,
@@ -46,12 +150,95 @@ pub struct MetadataUpdateEvent {
     pub token_id: u128,
 }
 
+#[contractevent]
+pub struct LabResultEvent {
+    pub token_id: u128,
+    pub lab: Address,
+    pub passed: bool,
+}
+
+#[contractevent]
+pub struct TokenFrozenEvent {
+    pub token_id: u128,
+    pub frozen_by: Address,
+    pub reason: String,
+}
+
+#[contractevent]
+pub struct TokenUnfrozenEvent {
+    pub token_id: u128,
+    pub unfrozen_by: Address,
+}
+
+#[contractevent]
+pub struct ComplianceAccessEvent {
+    pub token_id: u128,
+    pub accessed_by: Address,
+}
+
+#[contractevent]
+pub struct LicenseIssuedEvent {
+    pub account: Address,
+    pub number: String,
+    pub license_type: LicenseType,
+    pub expires_at: u64,
+}
+
+#[contractevent]
+pub struct LicenseStatusEvent {
+    pub account: Address,
+    pub license_type: LicenseType,
+    pub status: LicenseStatus,
+}
+
+#[contractevent]
+pub struct RecallEvent {
+    pub recall_id: u64,
+    pub issued_by: Address,
+    pub affected_count: u32,
+}
+
+#[contractevent]
+pub struct ThresholdsUpdatedEvent {
+    pub updated_by: Address,
+}
+
+#[contractevent]
+pub struct LabAccreditationEvent {
+    pub lab: Address,
+    pub expires_at: u64,
+}
+
 #[contractevent]
 pub struct WhitelistEvent {
     pub account: Address,
     pub added: bool,
 }
 
+#[contractevent]
+pub struct AttestationEvent {
+    pub account: Address,
+    pub attester: Address,
+    pub expires_at: u64,
+}
+
+#[contractevent]
+pub struct AdminProposedEvent {
+    pub current_admin: Address,
+    pub proposed_admin: Address,
+}
+
+#[contractevent]
+pub struct AdminTransferredEvent {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent]
+pub struct AdminRenouncedEvent {
+    pub account: Address,
+}
+
 #[contractevent]
 pub struct RoleGrantEvent {
     pub account: Address,
@@ -82,33 +269,43 @@ pub struct SeedNFTContract;
 impl NonFungibleToken for SeedNFTContract {
     type ContractType = Base;
     
-    fn transfer_from(e: &Env, spender: Address, from: Address, to: Address, token_id: u32) {
+    fn transfer(e: &Env, from: Address, to: Address, token_id: u32) {
         SeedNFT::require_not_paused(e);
+        SeedNFT::require_transferable(e, token_id);
+        SeedNFT::require_recipient_allowed(e, &to, token_id);
         
-        if !SeedNFT::is_whitelisted(e, &to) {
-            panic!("Recipient not whitelisted");
-        }
+        Self::ContractType::transfer(e, &from, &to, token_id);
+        SeedNFTContract::record_custody(e, token_id, Some(&from), Some(&to), &from, None);
+    }
+    
+    fn transfer_from(e: &Env, spender: Address, from: Address, to: Address, token_id: u32) {
+        SeedNFT::require_not_paused(e);
+        SeedNFT::require_transferable(e, token_id);
+        SeedNFT::require_recipient_allowed(e, &to, token_id);
         
         Self::ContractType::transfer_from(e, &spender, &from, &to, token_id);
+        SeedNFTContract::record_custody(e, token_id, Some(&from), Some(&to), &spender, None);
     }
     
     fn approve(e: &Env, approver: Address, approved: Address, token_id: u32, live_until_ledger: u32) {
         SeedNFT::require_not_paused(e);
+        SeedNFT::require_transferable(e, token_id);
         Self::ContractType::approve(e, &approver, &approved, token_id, live_until_ledger);
     }
 }
 
 #[contractimpl]
 impl SeedNFTContract {
-    pub fn initialize(env: Env, admin: Address, _name: String, _symbol: String) {
+    pub fn initialize(env: Env, admin: Address, name: String, symbol: String) {
         if env.storage().instance().has(&symbol_short!("INIT")) {
             panic!("Already initialized");
         }
         
         env.storage().instance().set(&symbol_short!("INIT"), &true);
         env.storage().instance().set(&symbol_short!("PAUSED"), &false);
+        Base::set_metadata(&env, String::from_str(&env, ""), name, symbol);
         
-        SeedNFT::grant_role(&env, &admin, ROLE_ADMIN);
+        Admins::init(&env, &admin);
     }
 
     pub fn mint(
@@ -121,18 +318,23 @@ impl SeedNFTContract {
         external_url: Option<String>,
         attributes: Vec<Attribute>,
     ) {
-        let token_id_u32 = token_id as u32;
-        <SeedNFTContract as NonFungibleToken>::ContractType::mint(&env, &to, token_id_u32);
+        if SeedNFT::get_metadata(&env, &token_id).is_some() {
+            panic!("Token already exists");
+        }
+        Self::mint_to(&env, &to, token_id, LifecycleState::Seed, None, &to);
         
         let metadata = SeedMetadata {
             state: LifecycleState::Seed,
             location: None,
             temperature: None,
             humidity: None,
-            lab_analysis: None,
             processor: None,
             distributor: None,
             consumer: None,
+            parent: None,
+            product_type: None,
+            quantity: None,
+            expires_at: None,
             updated_at: env.ledger().timestamp(),
             name,
             description,
@@ -142,169 +344,1484 @@ impl SeedNFTContract {
         };
         
         SeedNFT::store_metadata(&env, &token_id, &metadata);
+        SeedNFT::track_token_id(&env, &token_id);
         
         MintEvent { to, token_id }.publish(&env);
     }
 
-    pub fn update_state(
+    pub fn clone_from(
+        env: Env,
+        caller: Address,
+        parent_token_id: u128,
+        count: u32,
+        to: Address,
+    ) -> Vec<u128> {
+        caller.require_auth();
+        SeedNFT::require_not_paused(&env);
+        SeedNFT::require_licensed_role(&env, &caller, ROLE_CULTIVATOR);
+
+        if count == 0 || count > 100 {
+            panic!("Invalid clone count: must be between 1 and 100");
+        }
+
+        SeedNFT::require_not_frozen(&env, &parent_token_id);
+        let parent = SeedNFT::get_metadata(&env, &parent_token_id)
+            .unwrap_or_else(|| panic!("Token not found"));
+
+        match parent.state {
+            LifecycleState::PlantVegetative | LifecycleState::PlantFlowering => {}
+            _ => panic!("Parent is not a mother plant"),
+        }
+        Self::require_owner(&env, &caller, parent_token_id);
+
+        let timestamp = env.ledger().timestamp();
+        let mut token_ids = Vec::new(&env);
+
+        for _ in 0..count {
+            let token_id = SeedNFT::next_token_id(&env);
+
+            let mut metadata = parent.clone();
+            metadata.state = LifecycleState::PlantVegetative;
+            metadata.processor = None;
+            metadata.distributor = None;
+            metadata.consumer = None;
+            metadata.parent = Some(parent_token_id);
+            metadata.quantity = None;
+            metadata.updated_at = timestamp;
+
+            SeedNFT::store_metadata(&env, &token_id, &metadata);
+            Lineage::link(&env, &parent_token_id, &token_id);
+
+            let genesis = Genesis {
+                origin: Origin::Cloned,
+                state: LifecycleState::PlantVegetative,
+                sources: vec![&env, parent_token_id],
+                created_by: caller.clone(),
+                timestamp,
+            };
+            History::add_genesis(&env, &token_id, &genesis);
+            Self::mint_to(&env, &to, token_id, LifecycleState::PlantVegetative, Some(&parent_token_id), &caller);
+
+            CloneEvent {
+                parent_token_id,
+                token_id,
+                to: to.clone(),
+            }.publish(&env);
+
+            token_ids.push_back(token_id);
+        }
+
+        token_ids
+    }
+
+    pub fn split(
         env: Env,
         caller: Address,
         token_id: u128,
-        new_state: u32,
-        location: Option<String>,
-        temperature: Option<i32>,
-        humidity: Option<u32>,
-        notes: Option<String>,
-    ) {
+        lots: Vec<LotSpec>,
+    ) -> Vec<u128> {
         caller.require_auth();
         SeedNFT::require_not_paused(&env);
-        
-        let state = LifecycleState::from_u32(new_state)
-            .unwrap_or_else(|| panic!("Invalid state"));
-        
-        match state {
-            LifecycleState::Germinated | LifecycleState::PlantVegetative 
-            | LifecycleState::PlantFlowering | LifecycleState::PlantHarvested => {
-                SeedNFT::require_role(&env, &caller, ROLE_CULTIVATOR);
-            }
-            LifecycleState::Processed => {
-                SeedNFT::require_role(&env, &caller, ROLE_PROCESSOR);
-            }
-            LifecycleState::Distributed => {
-                SeedNFT::require_role(&env, &caller, ROLE_DISPENSARY);
-            }
-            LifecycleState::Consumed => {
-                SeedNFT::require_role(&env, &caller, ROLE_DISPENSARY);
-            }
-            _ => panic!("Invalid state transition"),
+        SeedNFT::require_licensed_role(&env, &caller, ROLE_CULTIVATOR);
+
+        if lots.is_empty() || lots.len() > 100 {
+            panic!("Invalid lot count: must be between 1 and 100");
         }
-        
-        let mut metadata = SeedNFT::get_metadata(&env, &token_id)
+
+        SeedNFT::require_not_frozen(&env, &token_id);
+        let mut parent = SeedNFT::get_metadata(&env, &token_id)
             .unwrap_or_else(|| panic!("Token not found"));
-        
-        if !metadata.state.can_transition_to(state) {
+
+        if !parent.state.can_transition_to(LifecycleState::Split) {
             panic!("Invalid state transition");
         }
-        
-        let from_state = metadata.state;
+        Self::require_owner(&env, &caller, token_id);
+
+        let mut total: u64 = 0;
+        for lot in lots.iter() {
+            total += lot.quantity;
+        }
+        let mut balance = Balances::debit(&env, &token_id, Outflow::Split, total);
+        // The Split parent is locked, so any mass not carried into a lot is
+        // booked as loss here rather than stranded on it.
+        if balance.remaining > 0 {
+            balance = Balances::debit(&env, &token_id, Outflow::Loss, balance.remaining);
+        }
+
         let timestamp = env.ledger().timestamp();
-        
-        metadata.state = state;
-        metadata.location = location;
-        metadata.temperature = temperature;
-        metadata.humidity = humidity;
-        metadata.updated_at = timestamp;
-        
-        match state {
-            LifecycleState::Processed => {
-                metadata.processor = Some(caller.clone());
-            }
-            LifecycleState::Distributed => {
-                metadata.distributor = Some(caller.clone());
-            }
-            LifecycleState::Consumed => {
-                let token_id_u32 = token_id as u32;
-                metadata.consumer = Some(<SeedNFTContract as NonFungibleToken>::ContractType::owner_of(&env, token_id_u32));
+        let mut token_ids = Vec::new(&env);
+
+        for lot in lots.iter() {
+            let lot_id = SeedNFT::next_token_id(&env);
+
+            let mut metadata = parent.clone();
+            metadata.parent = Some(token_id);
+            metadata.product_type = Some(lot.product_type.clone());
+            metadata.quantity = Some(lot.quantity);
+            metadata.updated_at = timestamp;
+
+            SeedNFT::store_metadata(&env, &lot_id, &metadata);
+            if metadata.expires_at.is_some() {
+                SeedNFT::track_expiry(&env, &lot_id);
             }
-            _ => {}
+            Balances::open(&env, &lot_id, balance.unit, lot.quantity);
+            Lineage::link(&env, &token_id, &lot_id);
+
+            let genesis = Genesis {
+                origin: Origin::Split,
+                state: metadata.state,
+                sources: vec![&env, token_id],
+                created_by: caller.clone(),
+                timestamp,
+            };
+            History::add_genesis(&env, &lot_id, &genesis);
+            Self::mint_to(&env, &caller, lot_id, parent.state, Some(&token_id), &caller);
+
+            SplitEvent {
+                parent_token_id: token_id,
+                token_id: lot_id,
+                product_type: lot.product_type,
+            }.publish(&env);
+
+            token_ids.push_back(lot_id);
         }
-        
-        SeedNFT::store_metadata(&env, &token_id, &metadata);
-        
+
+        let from_state = parent.state;
+        parent.state = LifecycleState::Split;
+        parent.quantity = Some(balance.remaining);
+        parent.updated_at = timestamp;
+        SeedNFT::store_metadata(&env, &token_id, &parent);
+        SeedNFT::end_shelf_life(&env, &token_id, &parent);
+
         let transition = StateTransition {
             from_state,
-            to_state: state,
+            to_state: LifecycleState::Split,
             timestamp,
             updated_by: caller.clone(),
-            notes,
+            notes: None,
+            related_token: None,
         };
         History::add_transition(&env, &token_id, &transition);
-        
+
         StateTransitionEvent {
             token_id,
             from_state: from_state.to_u32() as u128,
-            to_state: state.to_u32() as u128,
+            to_state: LifecycleState::Split.to_u32() as u128,
             updated_by: caller,
         }.publish(&env);
+
+        token_ids
     }
 
-    pub fn update_metadata(
+    pub fn merge(
         env: Env,
         caller: Address,
-        token_id: u128,
-        location: Option<String>,
-        temperature: Option<i32>,
-        humidity: Option<u32>,
-        lab_analysis: Option<String>,
-        opensea_metadata: Option<OpenSeaMetadata>,
-    ) {
+        input_token_ids: Vec<u128>,
+        product_spec: ProductSpec,
+        to: Address,
+    ) -> u128 {
         caller.require_auth();
         SeedNFT::require_not_paused(&env);
-        SeedNFT::require_role(&env, &caller, ROLE_CULTIVATOR);
-        
-        let mut metadata = SeedNFT::get_metadata(&env, &token_id)
-            .unwrap_or_else(|| panic!("Token not found"));
-        
-        if location.is_some() {
-            metadata.location = location;
-        }
-        if temperature.is_some() {
-            metadata.temperature = temperature;
-        }
-        if humidity.is_some() {
-            metadata.humidity = humidity;
-        }
-        if lab_analysis.is_some() {
-            metadata.lab_analysis = lab_analysis;
+        SeedNFT::require_licensed_role(&env, &caller, ROLE_PROCESSOR);
+
+        if input_token_ids.len() < 2 || input_token_ids.len() > 100 {
+            panic!("Invalid input count: must be between 2 and 100");
         }
-        if let Some(opensea) = opensea_metadata {
-            if opensea.name.is_some() {
-                metadata.name = opensea.name.unwrap();
-            }
-            if opensea.description.is_some() {
-                metadata.description = opensea.description.unwrap();
-            }
-            if opensea.image.is_some() {
-                metadata.image = opensea.image.unwrap();
+
+        let token_id = SeedNFT::next_token_id(&env);
+        let timestamp = env.ledger().timestamp();
+        let mut unit: Option<QuantityUnit> = None;
+        let mut total: u64 = 0;
+        let country = Borders::get_token_country(&env, &input_token_ids.get(0).unwrap());
+
+        for input_id in input_token_ids.iter() {
+            SeedNFT::require_not_frozen(&env, &input_id);
+            if Borders::get_token_country(&env, &input_id) != country {
+                panic!("Inputs are in different jurisdictions");
             }
-            if opensea.external_url.is_some() {
-                metadata.external_url = opensea.external_url;
+            let mut input = SeedNFT::get_metadata(&env, &input_id)
+                .unwrap_or_else(|| panic!("Token not found"));
+
+            if !input.state.can_transition_to(LifecycleState::Merged) {
+                panic!("Invalid state transition");
             }
-            if opensea.attributes.is_some() {
-                metadata.attributes = opensea.attributes.unwrap();
+            Self::require_owner_or_approved(&env, &caller, input_id);
+
+            let balance = Balances::get(&env, &input_id)
+                .unwrap_or_else(|| panic!("Quantity not recorded"));
+            if unit.is_some() && unit != Some(balance.unit) {
+                panic!("Input units do not match");
             }
+            unit = Some(balance.unit);
+
+            Balances::debit(&env, &input_id, Outflow::Merged, balance.remaining);
+            total += balance.remaining;
+
+            let from_state = input.state;
+            input.state = LifecycleState::Merged;
+            input.quantity = Some(0);
+            input.updated_at = timestamp;
+            SeedNFT::store_metadata(&env, &input_id, &input);
+            SeedNFT::end_shelf_life(&env, &input_id, &input);
+            Lineage::add_child(&env, &input_id, &token_id);
+
+            let transition = StateTransition {
+                from_state,
+                to_state: LifecycleState::Merged,
+                timestamp,
+                updated_by: caller.clone(),
+                notes: None,
+                related_token: Some(token_id),
+            };
+            History::add_transition(&env, &input_id, &transition);
+
+            StateTransitionEvent {
+                token_id: input_id,
+                from_state: from_state.to_u32() as u128,
+                to_state: LifecycleState::Merged.to_u32() as u128,
+                updated_by: caller.clone(),
+            }.publish(&env);
         }
-        metadata.updated_at = env.ledger().timestamp();
-        
+
+        if product_spec.quantity > total {
+            panic!("Outputs exceed available quantity");
+        }
+        let unit = unit.unwrap();
+
+        let metadata = SeedMetadata {
+            state: LifecycleState::PlantHarvested,
+            location: None,
+            temperature: None,
+            humidity: None,
+            processor: None,
+            distributor: None,
+            consumer: None,
+            parent: None,
+            product_type: Some(product_spec.product_type),
+            quantity: Some(product_spec.quantity),
+            expires_at: None,
+            updated_at: timestamp,
+            name: product_spec.name,
+            description: product_spec.description,
+            image: product_spec.image,
+            external_url: None,
+            attributes: Vec::new(&env),
+        };
+
         SeedNFT::store_metadata(&env, &token_id, &metadata);
-        
-        MetadataUpdateEvent { token_id }.publish(&env);
-    }
+        Balances::open(&env, &token_id, unit, total);
+        if total > product_spec.quantity {
+            Balances::debit(&env, &token_id, Outflow::Loss, total - product_spec.quantity);
+        }
+        Lineage::set_inputs(&env, &token_id, &input_token_ids);
 
-    pub fn get_metadata(env: Env, token_id: u128) -> Option<SeedMetadata> {
-        SeedNFT::get_metadata(&env, &token_id)
-    }
+        let genesis = Genesis {
+            origin: Origin::Merged,
+            state: LifecycleState::PlantHarvested,
+            sources: input_token_ids.clone(),
+            created_by: caller.clone(),
+            timestamp,
+        };
+        History::add_genesis(&env, &token_id, &genesis);
+        Self::mint_to(&env, &to, token_id, LifecycleState::PlantHarvested, Some(&input_token_ids.get(0).unwrap()), &caller);
 
-    pub fn get_history(env: Env, token_id: u128) -> Vec<StateTransition> {
-        History::get_history(&env, &token_id)
-    }
+        MergeEvent {
+            token_id,
+            inputs: input_token_ids,
+            to,
+        }.publish(&env);
 
-    pub fn add_to_whitelist(env: Env, caller: Address, account: Address) {
-        caller.require_auth();
-        SeedNFT::add_to_whitelist(&env, &caller, &account);
-        WhitelistEvent { account, added: true }.publish(&env);
+        token_id
     }
 
-    pub fn remove_from_whitelist(env: Env, caller: Address, account: Address) {
-        caller.require_auth();
+    pub fn set_quantity(
+        env: Env,
+        caller: Address,
+        token_id: u128,
+        quantity: u64,
+        unit: QuantityUnit,
+    ) {
+        caller.require_auth();
+        SeedNFT::require_not_paused(&env);
+        let role = if SeedNFT::has_role(&env, &caller, ROLE_PROCESSOR) {
+            ROLE_PROCESSOR
+        } else {
+            ROLE_CULTIVATOR
+        };
+        SeedNFT::require_licensed_role(&env, &caller, role);
+
+        SeedNFT::require_not_frozen(&env, &token_id);
+        let mut metadata = SeedNFT::get_metadata(&env, &token_id)
+            .unwrap_or_else(|| panic!("Token not found"));
+        if metadata.state.is_locked() {
+            panic!("Token is locked");
+        }
+        Self::require_owner_or_approved(&env, &caller, token_id);
+
+        // A re-weigh can only account for loss (drying, trimming, processing);
+        // anything above the remaining balance would break reconciliation.
+        let balance = match Balances::get(&env, &token_id) {
+            None => Balances::open(&env, &token_id, unit, quantity),
+            Some(balance) => {
+                if balance.unit != unit {
+                    panic!("Quantity unit does not match");
+                }
+                if quantity > balance.remaining {
+                    panic!("Quantity exceeds mass balance");
+                }
+                Balances::debit(&env, &token_id, Outflow::Loss, balance.remaining - quantity)
+            }
+        };
+
+        metadata.quantity = Some(balance.remaining);
+        metadata.updated_at = env.ledger().timestamp();
+
+        SeedNFT::store_metadata(&env, &token_id, &metadata);
+
+        QuantityRecordedEvent { token_id, quantity, unit }.publish(&env);
+    }
+
+    pub fn sell_partial(
+        env: Env,
+        caller: Address,
+        token_id: u128,
+        quantity: u64,
+        consumer: Address,
+        mint_unit: bool,
+    ) -> Option<u128> {
+        caller.require_auth();
+        SeedNFT::require_not_paused(&env);
+        SeedNFT::require_licensed_role(&env, &caller, ROLE_DISPENSARY);
+
+        if quantity == 0 {
+            panic!("Invalid quantity");
+        }
+
+        SeedNFT::require_not_frozen(&env, &token_id);
+        let mut metadata = SeedNFT::get_metadata(&env, &token_id)
+            .unwrap_or_else(|| panic!("Token not found"));
+
+        if metadata.state != LifecycleState::Distributed {
+            panic!("Token is not available for sale");
+        }
+        Self::require_owner(&env, &caller, token_id);
+        if SeedNFT::is_expired(&env, &metadata) {
+            panic_with_error!(&env, Error::ProductExpired);
+        }
+        if !SeedNFT::has_valid_attestation(&env, &consumer) {
+            panic_with_error!(&env, Error::MissingAttestation);
+        }
+        SeedNFT::require_recipient_for(&env, &consumer, LifecycleState::Distributed, Some(&token_id));
+        let unit = Balances::get(&env, &token_id)
+            .unwrap_or_else(|| panic!("Quantity not recorded"))
+            .unit;
+        let category = Self::purchase_category(&env, &metadata);
+        if let Err(error) = Purchases::record(&env, &consumer, &category, unit, quantity) {
+            panic_with_error!(&env, error);
+        }
+
+        let balance = Balances::debit(&env, &token_id, Outflow::Sold, quantity);
+        let timestamp = env.ledger().timestamp();
+
+        // Under the burn policy a unit token would be destroyed as soon as it
+        // was minted, so the sale record alone is kept.
+        let keep_units = SeedNFT::get_consumed_policy(&env) != ConsumedPolicy::Burn;
+        let unit_token = if mint_unit && keep_units {
+            let unit_id = SeedNFT::next_token_id(&env);
+
+            let mut unit_metadata = metadata.clone();
+            unit_metadata.state = LifecycleState::Consumed;
+            unit_metadata.consumer = Some(consumer.clone());
+            unit_metadata.parent = Some(token_id);
+            unit_metadata.quantity = Some(quantity);
+            unit_metadata.updated_at = timestamp;
+
+            SeedNFT::store_metadata(&env, &unit_id, &unit_metadata);
+            Balances::open(&env, &unit_id, unit, quantity);
+            Lineage::link(&env, &token_id, &unit_id);
+
+            let genesis = Genesis {
+                origin: Origin::Sold,
+                state: LifecycleState::Consumed,
+                sources: vec![&env, token_id],
+                created_by: caller.clone(),
+                timestamp,
+            };
+            History::add_genesis(&env, &unit_id, &genesis);
+            Self::mint_to(&env, &consumer, unit_id, LifecycleState::Distributed, Some(&token_id), &caller);
+
+            Some(unit_id)
+        } else {
+            None
+        };
+
+        let sale = Sale {
+            seller: caller.clone(),
+            consumer: consumer.clone(),
+            quantity,
+            unit,
+            timestamp,
+            unit_token,
+        };
+        History::add_sale(&env, &token_id, &sale);
+
+        metadata.quantity = Some(balance.remaining);
+        metadata.updated_at = timestamp;
+
+        if balance.remaining == 0 {
+            metadata.state = LifecycleState::Consumed;
+
+            let transition = StateTransition {
+                from_state: LifecycleState::Distributed,
+                to_state: LifecycleState::Consumed,
+                timestamp,
+                updated_by: caller.clone(),
+                notes: Some(String::from_str(&env, "Lot sold out")),
+                related_token: None,
+            };
+            History::add_transition(&env, &token_id, &transition);
+
+            StateTransitionEvent {
+                token_id,
+                from_state: LifecycleState::Distributed.to_u32() as u128,
+                to_state: LifecycleState::Consumed.to_u32() as u128,
+                updated_by: caller.clone(),
+            }.publish(&env);
+        }
+
+        SeedNFT::store_metadata(&env, &token_id, &metadata);
+        if balance.remaining == 0 {
+            SeedNFT::end_shelf_life(&env, &token_id, &metadata);
+            Self::finalize_consumed(&env, token_id, &caller);
+        }
+
+        SaleEvent {
+            token_id,
+            quantity,
+            remaining: balance.remaining,
+        }.publish(&env);
+
+        unit_token
+    }
+
+    pub fn record_destruction(
+        env: Env,
+        caller: Address,
+        token_id: u128,
+        quantity: u64,
+        method: DestructionMethod,
+        witnesses: Vec<Address>,
+    ) {
+        caller.require_auth();
+        SeedNFT::require_not_paused(&env);
+
+        if witnesses.is_empty() {
+            panic!("At least one witness required");
+        }
+        for (i, witness) in witnesses.iter().enumerate() {
+            if witness == caller {
+                panic!("Caller cannot witness own destruction");
+            }
+            if witnesses.first_index_of(&witness) != Some(i as u32) {
+                panic!("Duplicate witness");
+            }
+            witness.require_auth();
+        }
+
+        let mut metadata = SeedNFT::get_metadata(&env, &token_id)
+            .unwrap_or_else(|| panic!("Token not found"));
+        if metadata.state.is_locked() {
+            panic!("Token is locked");
+        }
+
+        let owner = <SeedNFTContract as NonFungibleToken>::ContractType::owner_of(&env, token_id as u32);
+        if caller != owner {
+            SeedNFT::require_regulator(&env, &caller);
+        }
+
+        // Waste has to reconcile against the lot's mass balance. Seeds and
+        // plants have no recorded quantity, so they are destroyed whole by
+        // passing a quantity of zero and are booked as a single unit.
+        let (destroyed, unit, remaining) = match Balances::get(&env, &token_id) {
+            Some(_) => {
+                if quantity == 0 {
+                    panic!("Invalid quantity");
+                }
+                let balance = Balances::debit(&env, &token_id, Outflow::Wasted, quantity);
+                metadata.quantity = Some(balance.remaining);
+                (quantity, balance.unit, balance.remaining)
+            }
+            None => {
+                if quantity != 0 {
+                    panic!("Quantity not recorded");
+                }
+                (1, QuantityUnit::Units, 0)
+            }
+        };
+        let timestamp = env.ledger().timestamp();
+
+        let destruction = Destruction {
+            quantity: destroyed,
+            unit,
+            method,
+            witnesses,
+            destroyed_by: caller.clone(),
+            timestamp,
+            remaining,
+        };
+        History::add_destruction(&env, &token_id, &destruction);
+
+        if remaining == 0 {
+            let from_state = metadata.state;
+            metadata.state = LifecycleState::Destroyed;
+            SeedNFT::end_shelf_life(&env, &token_id, &metadata);
+
+            let transition = StateTransition {
+                from_state,
+                to_state: LifecycleState::Destroyed,
+                timestamp,
+                updated_by: caller.clone(),
+                notes: Some(String::from_str(&env, "Destroyed")),
+                related_token: None,
+            };
+            History::add_transition(&env, &token_id, &transition);
+            Self::record_custody(&env, token_id as u32, Some(&owner), None, &caller, None);
+
+            StateTransitionEvent {
+                token_id,
+                from_state: from_state.to_u32() as u128,
+                to_state: LifecycleState::Destroyed.to_u32() as u128,
+                updated_by: caller.clone(),
+            }.publish(&env);
+        }
+
+        metadata.updated_at = timestamp;
+        SeedNFT::store_metadata(&env, &token_id, &metadata);
+
+        DestructionEvent {
+            token_id,
+            quantity: destroyed,
+            method,
+            destroyed_by: caller,
+        }.publish(&env);
+    }
+
+    pub fn update_state(
+        env: Env,
+        caller: Address,
+        token_id: u128,
+        new_state: u32,
+        location: Option<String>,
+        temperature: Option<i32>,
+        humidity: Option<u32>,
+        notes: Option<String>,
+    ) {
+        caller.require_auth();
+        SeedNFT::require_not_paused(&env);
+        
+        let state = LifecycleState::from_u32(new_state)
+            .unwrap_or_else(|| panic!("Invalid state"));
+        Self::require_state_role(&env, &caller, state);
+        
+        if let Err(error) = Self::apply_state_update(
+            &env,
+            &caller,
+            token_id,
+            state,
+            location,
+            temperature,
+            humidity,
+            notes,
+        ) {
+            match error {
+                Error::TokenNotFound => panic!("Token not found"),
+                Error::InvalidStateTransition => panic!("Invalid state transition"),
+                _ => panic_with_error!(&env, error),
+            }
+        }
+    }
+
+    pub fn update_state_batch(
+        env: Env,
+        caller: Address,
+        token_ids: Vec<u128>,
+        new_state: u32,
+        location: Option<String>,
+        temperature: Option<i32>,
+        humidity: Option<u32>,
+        notes: Option<String>,
+    ) -> Vec<BatchOutcome> {
+        caller.require_auth();
+        SeedNFT::require_not_paused(&env);
+        
+        if token_ids.len() > SeedNFT::get_batch_limit(&env) {
+            panic!("Batch size too large");
+        }
+        
+        let state = LifecycleState::from_u32(new_state)
+            .unwrap_or_else(|| panic!("Invalid state"));
+        Self::require_state_role(&env, &caller, state);
+        
+        let mut outcomes = Vec::new(&env);
+        for token_id in token_ids.iter() {
+            let result = Self::apply_state_update(
+                &env,
+                &caller,
+                token_id,
+                state,
+                location.clone(),
+                temperature,
+                humidity,
+                notes.clone(),
+            );
+            outcomes.push_back(BatchOutcome {
+                token_id,
+                success: result.is_ok(),
+                error: result.err().map(|error| error as u32),
+            });
+        }
+        
+        outcomes
+    }
+
+    pub fn set_batch_limit(env: Env, caller: Address, limit: u32) {
+        caller.require_auth();
+        SeedNFT::set_batch_limit(&env, &caller, limit);
+    }
+
+    pub fn get_batch_limit(env: Env) -> u32 {
+        SeedNFT::get_batch_limit(&env)
+    }
+
+    pub fn set_state_requirements(
+        env: Env,
+        caller: Address,
+        state: u32,
+        requirements: StateRequirements,
+    ) {
+        caller.require_auth();
+        let state = LifecycleState::from_u32(state)
+            .unwrap_or_else(|| panic!("Invalid state"));
+        Requirements::set(&env, &caller, state, &requirements);
+    }
+
+    pub fn get_state_requirements(env: Env, state: u32) -> StateRequirements {
+        let state = LifecycleState::from_u32(state)
+            .unwrap_or_else(|| panic!("Invalid state"));
+        Requirements::get(&env, state)
+    }
+
+    pub fn transfer_with_manifest(env: Env, from: Address, to: Address, token_id: u32, manifest: String) {
+        SeedNFT::require_not_paused(&env);
+        SeedNFT::require_transferable(&env, token_id);
+        SeedNFT::require_recipient_allowed(&env, &to, token_id);
+
+        <SeedNFTContract as NonFungibleToken>::ContractType::transfer(&env, &from, &to, token_id);
+        Self::record_custody(&env, token_id, Some(&from), Some(&to), &from, Some(manifest));
+    }
+
+    pub fn set_home_country(env: Env, caller: Address, country: String) {
+        caller.require_auth();
+        Borders::set_home_country(&env, &caller, &country);
+    }
+
+    pub fn get_home_country(env: Env) -> Option<String> {
+        Borders::get_home_country(&env)
+    }
+
+    pub fn set_account_country(env: Env, caller: Address, account: Address, country: String) {
+        caller.require_auth();
+        Borders::set_account_country(&env, &caller, &account, &country);
+    }
+
+    pub fn get_account_country(env: Env, account: Address) -> Option<String> {
+        Borders::get_account_country(&env, &account)
+    }
+
+    pub fn get_token_country(env: Env, token_id: u128) -> Option<String> {
+        Borders::get_token_country(&env, &token_id)
+    }
+
+    pub fn record_export(
+        env: Env,
+        caller: Address,
+        token_id: u128,
+        permit: ExportPermit,
+        clearance: CustomsClearance,
+    ) {
+        caller.require_auth();
+        SeedNFT::require_not_paused(&env);
+        SeedNFT::require_not_frozen(&env, &token_id);
+
+        let metadata = SeedNFT::get_metadata(&env, &token_id)
+            .unwrap_or_else(|| panic!("Token not found"));
+        if metadata.state.is_locked() {
+            panic!("Token is locked");
+        }
+        let owner = <SeedNFTContract as NonFungibleToken>::ContractType::owner_of(&env, token_id as u32);
+        if caller != owner {
+            panic!("Only the owner can export");
+        }
+
+        let origin = Borders::get_token_country(&env, &token_id)
+            .unwrap_or_else(|| panic!("Origin country not set"));
+        if permit.origin_country != origin {
+            panic!("Permit origin does not match token location");
+        }
+        if permit.destination_country == origin {
+            panic!("Destination must be a foreign jurisdiction");
+        }
+        if permit.expires_at <= env.ledger().timestamp() {
+            panic!("Export permit expired");
+        }
+        Borders::require_issuer(&env, &permit);
+        Borders::require_customs(&env, &clearance);
+
+        Borders::set_pending_export(&env, &token_id, &permit);
+        Self::record_crossing(&env, token_id, CrossingKind::Export, &permit, clearance, &caller);
+    }
+
+    pub fn record_import(
+        env: Env,
+        caller: Address,
+        token_id: u128,
+        permit_number: String,
+        clearance: CustomsClearance,
+    ) {
+        caller.require_auth();
+        SeedNFT::require_not_paused(&env);
+        SeedNFT::require_not_frozen(&env, &token_id);
+
+        let metadata = SeedNFT::get_metadata(&env, &token_id)
+            .unwrap_or_else(|| panic!("Token not found"));
+        if metadata.state.is_locked() {
+            panic!("Token is locked");
+        }
+        let owner = <SeedNFTContract as NonFungibleToken>::ContractType::owner_of(&env, token_id as u32);
+        if caller != owner {
+            panic!("Only the owner can import");
+        }
+        let permit = match Borders::get_pending_export(&env, &token_id) {
+            Some(permit) if permit.permit_number == permit_number => permit,
+            _ => panic!("No matching export permit"),
+        };
+        Borders::require_customs(&env, &clearance);
+
+        Borders::set_token_country(&env, &token_id, &permit.destination_country);
+        Borders::clear_pending_export(&env, &token_id);
+        Self::record_crossing(&env, token_id, CrossingKind::Import, &permit, clearance, &caller);
+    }
+
+    pub fn get_pending_export(env: Env, token_id: u128) -> Option<ExportPermit> {
+        Borders::get_pending_export(&env, &token_id)
+    }
+
+    pub fn set_transfer_rule(env: Env, caller: Address, state: u32, rule: TransferRule) {
+        caller.require_auth();
+        let state = LifecycleState::from_u32(state)
+            .unwrap_or_else(|| panic!("Invalid state"));
+        TransferRules::set(&env, &caller, state, &rule);
+    }
+
+    pub fn get_transfer_rule(env: Env, state: u32) -> TransferRule {
+        let state = LifecycleState::from_u32(state)
+            .unwrap_or_else(|| panic!("Invalid state"));
+        TransferRules::get(&env, state)
+    }
+
+    pub fn set_shelf_life(env: Env, caller: Address, seconds: u64) {
+        caller.require_auth();
+        SeedNFT::set_shelf_life(&env, &caller, seconds);
+    }
+
+    pub fn get_shelf_life(env: Env) -> u64 {
+        SeedNFT::get_shelf_life(&env)
+    }
+
+    pub fn set_consumed_policy(env: Env, caller: Address, policy: ConsumedPolicy) {
+        caller.require_auth();
+        SeedNFT::set_consumed_policy(&env, &caller, policy);
+    }
+
+    pub fn get_consumed_policy(env: Env) -> ConsumedPolicy {
+        SeedNFT::get_consumed_policy(&env)
+    }
+
+    pub fn set_purchase_limit(env: Env, caller: Address, limit: PurchaseLimit) {
+        caller.require_auth();
+        SeedNFT::require_role(&env, &caller, ROLE_ADMIN);
+        if limit.window == 0 {
+            panic!("Invalid purchase window");
+        }
+        Purchases::set_limit(&env, &limit);
+    }
+
+    pub fn remove_purchase_limit(env: Env, caller: Address, category: String, unit: QuantityUnit) {
+        caller.require_auth();
+        SeedNFT::require_role(&env, &caller, ROLE_ADMIN);
+        Purchases::remove_limit(&env, &category, unit);
+    }
+
+    pub fn get_purchase_limits(env: Env) -> Vec<PurchaseLimit> {
+        Purchases::get_limits(&env)
+    }
+
+    pub fn remaining_allowance(env: Env, consumer: Address) -> Vec<Allowance> {
+        Purchases::remaining_allowance(&env, &consumer)
+    }
+
+    pub fn get_expiring_count(env: Env) -> u32 {
+        SeedNFT::expiring_len(&env)
+    }
+
+    /// Pages through the expiring index, returning the in-stock tokens whose
+    /// shelf life has ended, so a page may hold fewer than `limit` tokens.
+    pub fn get_expired_tokens(env: Env, cursor: u32, limit: u32) -> Vec<u128> {
+        let mut result = Vec::new(&env);
+
+        for token_id in SeedNFT::get_expiring(&env, cursor, limit).iter() {
+            if let Some(metadata) = SeedNFT::get_metadata(&env, &token_id) {
+                let in_stock = matches!(
+                    metadata.state,
+                    LifecycleState::Processed | LifecycleState::Distributed
+                );
+                if in_stock && SeedNFT::is_expired(&env, &metadata) {
+                    result.push_back(token_id);
+                }
+            }
+        }
+
+        result
+    }
+
+    fn require_state_role(env: &Env, caller: &Address, state: LifecycleState) {
+        match state {
+            LifecycleState::Germinated | LifecycleState::PlantVegetative 
+            | LifecycleState::PlantFlowering | LifecycleState::PlantHarvested => {
+                SeedNFT::require_licensed_role(env, caller, ROLE_CULTIVATOR);
+            }
+            LifecycleState::Processed => {
+                SeedNFT::require_licensed_role(env, caller, ROLE_PROCESSOR);
+            }
+            LifecycleState::Distributed => {
+                SeedNFT::require_licensed_role(env, caller, ROLE_DISPENSARY);
+            }
+            LifecycleState::Consumed => {
+                SeedNFT::require_licensed_role(env, caller, ROLE_DISPENSARY);
+            }
+            _ => panic!("Invalid state transition"),
+        }
+    }
+
+    fn apply_state_update(
+        env: &Env,
+        caller: &Address,
+        token_id: u128,
+        state: LifecycleState,
+        location: Option<String>,
+        temperature: Option<i32>,
+        humidity: Option<u32>,
+        notes: Option<String>,
+    ) -> Result<(), Error> {
+        let mut metadata = SeedNFT::get_metadata(env, &token_id)
+            .ok_or(Error::TokenNotFound)?;
+        
+        if Compliance::is_frozen(env, &token_id) {
+            return Err(Error::TokenFrozen);
+        }
+        
+        if !metadata.state.can_transition_to(state) {
+            return Err(Error::InvalidStateTransition);
+        }
+        
+        let owner = <SeedNFTContract as NonFungibleToken>::ContractType::owner_of(env, token_id as u32);
+        Requirements::check(env, state, &token_id, &metadata, &location, &owner)?;
+        
+        if state == LifecycleState::Consumed {
+            if SeedNFT::is_expired(env, &metadata) {
+                return Err(Error::ProductExpired);
+            }
+            if !SeedNFT::has_valid_attestation(env, &owner) {
+                return Err(Error::MissingAttestation);
+            }
+            if let Some(balance) = Balances::get(env, &token_id) {
+                let category = Self::purchase_category(env, &metadata);
+                Purchases::record(env, &owner, &category, balance.unit, balance.remaining)?;
+            }
+        }
+        
+        let from_state = metadata.state;
+        let timestamp = env.ledger().timestamp();
+        
+        metadata.state = state;
+        metadata.location = location;
+        metadata.temperature = temperature;
+        metadata.humidity = humidity;
+        metadata.updated_at = timestamp;
+        
+        match state {
+            LifecycleState::Processed => {
+                metadata.processor = Some(caller.clone());
+                SeedNFT::start_shelf_life(env, &token_id, &mut metadata);
+                Recalls::index_processor(env, caller, &token_id);
+            }
+            LifecycleState::Distributed => {
+                metadata.distributor = Some(caller.clone());
+            }
+            LifecycleState::Consumed => {
+                metadata.consumer = Some(owner);
+            }
+            _ => {}
+        }
+        
+        SeedNFT::store_metadata(env, &token_id, &metadata);
+        
+        let transition = StateTransition {
+            from_state,
+            to_state: state,
+            timestamp,
+            updated_by: caller.clone(),
+            notes,
+            related_token: None,
+        };
+        History::add_transition(env, &token_id, &transition);
+        
+        StateTransitionEvent {
+            token_id,
+            from_state: from_state.to_u32() as u128,
+            to_state: state.to_u32() as u128,
+            updated_by: caller.clone(),
+        }.publish(env);
+        
+        if state == LifecycleState::Consumed {
+            SeedNFT::end_shelf_life(env, &token_id, &metadata);
+            Self::finalize_consumed(env, token_id, caller);
+        }
+        
+        Ok(())
+    }
+
+    /// Every token enters circulation here, so a mint faces the same recipient
+    /// rules as a transfer of a token in `state` from `source`.
+    fn mint_to(
+        env: &Env,
+        to: &Address,
+        token_id: u128,
+        state: LifecycleState,
+        source: Option<&u128>,
+        operator: &Address,
+    ) {
+        SeedNFT::require_recipient_for(env, to, state, source);
+        if let Some(source) = source {
+            Borders::inherit_country(env, source, &token_id);
+        }
+        <SeedNFTContract as NonFungibleToken>::ContractType::mint(env, to, token_id as u32);
+        Self::record_custody(env, token_id as u32, None, Some(to), operator, None);
+    }
+
+    fn require_owner(env: &Env, caller: &Address, token_id: u128) {
+        let owner = <SeedNFTContract as NonFungibleToken>::ContractType::owner_of(env, token_id as u32);
+        if *caller != owner {
+            panic!("Caller is not the token owner");
+        }
+    }
+
+    fn require_owner_or_approved(env: &Env, caller: &Address, token_id: u128) {
+        let owner = <SeedNFTContract as NonFungibleToken>::ContractType::owner_of(env, token_id as u32);
+        if *caller == owner {
+            return;
+        }
+        let approved = <SeedNFTContract as NonFungibleToken>::ContractType::get_approved(env, token_id as u32);
+        if approved.as_ref() != Some(caller)
+            && !<SeedNFTContract as NonFungibleToken>::ContractType::is_approved_for_all(env, &owner, caller)
+        {
+            panic!("Caller is not the token owner or approved");
+        }
+    }
+
+    fn purchase_category(env: &Env, metadata: &SeedMetadata) -> String {
+        metadata
+            .product_type
+            .clone()
+            .unwrap_or_else(|| String::from_str(env, "general"))
+    }
+
+    fn record_crossing(
+        env: &Env,
+        token_id: u128,
+        kind: CrossingKind,
+        permit: &ExportPermit,
+        clearance: CustomsClearance,
+        recorded_by: &Address,
+    ) {
+        let crossing = BorderCrossing {
+            kind,
+            permit_number: permit.permit_number.clone(),
+            issued_by: permit.issued_by.clone(),
+            origin_country: permit.origin_country.clone(),
+            destination_country: permit.destination_country.clone(),
+            clearance,
+            recorded_by: recorded_by.clone(),
+            timestamp: env.ledger().timestamp(),
+        };
+        History::add_crossing(env, &token_id, &crossing);
+
+        BorderCrossingEvent {
+            token_id,
+            kind,
+            permit_number: permit.permit_number.clone(),
+            destination_country: permit.destination_country.clone(),
+        }.publish(env);
+    }
+
+    /// Mints record custody from nobody and burns or final disposal to nobody,
+    /// so the timeline covers the token's whole life.
+    fn record_custody(
+        env: &Env,
+        token_id: u32,
+        from: Option<&Address>,
+        to: Option<&Address>,
+        operator: &Address,
+        manifest: Option<String>,
+    ) {
+        let custody = Custody {
+            from: from.cloned(),
+            to: to.cloned(),
+            operator: operator.clone(),
+            timestamp: env.ledger().timestamp(),
+            manifest,
+        };
+        History::add_custody(env, &(token_id as u128), &custody);
+    }
+
+    fn require_registry(env: &Env) -> Address {
+        let registry = Recalls::get_registry(env)
+            .unwrap_or_else(|| panic!("Registry not configured"));
+        registry.require_auth();
+        registry
+    }
+
+    fn finalize_consumed(env: &Env, token_id: u128, operator: &Address) {
+        if SeedNFT::get_consumed_policy(env) != ConsumedPolicy::Burn {
+            return;
+        }
+
+        let owner = <SeedNFTContract as NonFungibleToken>::ContractType::owner_of(env, token_id as u32);
+        <SeedNFTContract as NonFungibleToken>::ContractType::update(env, Some(&owner), None, token_id as u32);
+        Self::record_custody(env, token_id as u32, Some(&owner), None, operator, None);
+
+        BurnEvent { token_id, owner }.publish(env);
+    }
+
+    pub fn update_metadata(
+        env: Env,
+        caller: Address,
+        token_id: u128,
+        location: Option<String>,
+        temperature: Option<i32>,
+        humidity: Option<u32>,
+        opensea_metadata: Option<OpenSeaMetadata>,
+    ) {
+        caller.require_auth();
+        SeedNFT::require_not_paused(&env);
+        SeedNFT::require_licensed_role(&env, &caller, ROLE_CULTIVATOR);
+        
+        let mut metadata = SeedNFT::get_metadata(&env, &token_id)
+            .unwrap_or_else(|| panic!("Token not found"));
+        
+        if location.is_some() {
+            metadata.location = location;
+        }
+        if temperature.is_some() {
+            metadata.temperature = temperature;
+        }
+        if humidity.is_some() {
+            metadata.humidity = humidity;
+        }
+        if let Some(opensea) = opensea_metadata {
+            if opensea.name.is_some() {
+                metadata.name = opensea.name.unwrap();
+            }
+            if opensea.description.is_some() {
+                metadata.description = opensea.description.unwrap();
+            }
+            if opensea.image.is_some() {
+                metadata.image = opensea.image.unwrap();
+            }
+            if opensea.external_url.is_some() {
+                metadata.external_url = opensea.external_url;
+            }
+            if opensea.attributes.is_some() {
+                metadata.attributes = opensea.attributes.unwrap();
+            }
+        }
+        metadata.updated_at = env.ledger().timestamp();
+        
+        SeedNFT::store_metadata(&env, &token_id, &metadata);
+        
+        MetadataUpdateEvent { token_id }.publish(&env);
+    }
+
+    pub fn submit_lab_result(env: Env, lab: Address, token_id: u128, submission: LabSubmission) {
+        lab.require_auth();
+        SeedNFT::require_not_paused(&env);
+        SeedNFT::require_licensed_role(&env, &lab, ROLE_LAB);
+        Accreditations::require_accredited(&env, &lab);
+
+        let mut metadata = SeedNFT::get_metadata(&env, &token_id)
+            .unwrap_or_else(|| panic!("Token not found"));
+
+        let mut result = LabResult::from_submission(&env, &lab, submission);
+        Compliance::evaluate(&env, &mut result);
+        LabResults::add(&env, &token_id, &result);
+
+        metadata.updated_at = env.ledger().timestamp();
+        SeedNFT::store_metadata(&env, &token_id, &metadata);
+
+        LabResultEvent { token_id, lab: lab.clone(), passed: result.passed }.publish(&env);
+
+        if !result.passed && !Compliance::is_frozen(&env, &token_id) {
+            let reason = String::from_str(&env, "Failed lab analysis");
+            Compliance::freeze(&env, &token_id, reason.clone(), &lab);
+            TokenFrozenEvent { token_id, frozen_by: lab, reason }.publish(&env);
+        }
+    }
+
+    pub fn set_compliance_thresholds(env: Env, caller: Address, thresholds: ComplianceThresholds) {
+        caller.require_auth();
+        SeedNFT::require_regulator(&env, &caller);
+        Compliance::set_thresholds(&env, &thresholds);
+        ThresholdsUpdatedEvent { updated_by: caller }.publish(&env);
+    }
+
+    pub fn get_compliance_thresholds(env: Env) -> ComplianceThresholds {
+        Compliance::get_thresholds(&env)
+    }
+
+    pub fn freeze_token(env: Env, caller: Address, token_id: u128, reason: String) {
+        caller.require_auth();
+        SeedNFT::require_regulator(&env, &caller);
+
+        if SeedNFT::get_metadata(&env, &token_id).is_none() {
+            panic!("Token not found");
+        }
+
+        Compliance::freeze(&env, &token_id, reason.clone(), &caller);
+        TokenFrozenEvent { token_id, frozen_by: caller, reason }.publish(&env);
+    }
+
+    pub fn unfreeze_token(env: Env, caller: Address, token_id: u128) {
+        caller.require_auth();
+        SeedNFT::require_regulator(&env, &caller);
+        Compliance::unfreeze(&env, &token_id);
+        TokenUnfrozenEvent { token_id, unfrozen_by: caller }.publish(&env);
+    }
+
+    /// Called by the registry when a seed is frozen; freezes the seed's token
+    /// and its descendants.
+    pub fn freeze_seed_tokens(env: Env, token_id: u128, reason: String) {
+        let registry = Self::require_registry(&env);
+
+        for token_id in Recalls::resolve(&env, &RecallScope::Token(token_id)).iter() {
+            if SeedNFT::get_metadata(&env, &token_id).is_none() || Compliance::is_frozen(&env, &token_id) {
+                continue;
+            }
+            Compliance::freeze(&env, &token_id, reason.clone(), &registry);
+            TokenFrozenEvent {
+                token_id,
+                frozen_by: registry.clone(),
+                reason: reason.clone(),
+            }.publish(&env);
+        }
+    }
+
+    /// Lifts the freezes placed by `freeze_seed_tokens`, leaving any other
+    /// freeze in place.
+    pub fn unfreeze_seed_tokens(env: Env, token_id: u128) {
+        let registry = Self::require_registry(&env);
+
+        for token_id in Recalls::resolve(&env, &RecallScope::Token(token_id)).iter() {
+            let frozen_by_registry = Compliance::get_freeze(&env, &token_id)
+                .map(|freeze| freeze.frozen_by == registry)
+                .unwrap_or(false);
+            if frozen_by_registry {
+                Compliance::unfreeze(&env, &token_id);
+                TokenUnfrozenEvent { token_id, unfrozen_by: registry.clone() }.publish(&env);
+            }
+        }
+    }
+
+    pub fn recall(env: Env, caller: Address, scope: RecallScope, reason: String) -> u64 {
+        caller.require_auth();
+        SeedNFT::require_regulator(&env, &caller);
+
+        let affected = Recalls::resolve(&env, &scope);
+        for token_id in affected.iter() {
+            if SeedNFT::get_metadata(&env, &token_id).is_none() || Compliance::is_frozen(&env, &token_id) {
+                continue;
+            }
+            Compliance::freeze(&env, &token_id, reason.clone(), &caller);
+            TokenFrozenEvent {
+                token_id,
+                frozen_by: caller.clone(),
+                reason: reason.clone(),
+            }.publish(&env);
+        }
+
+        let affected_count = affected.len();
+        let recall = Recalls::store(&env, scope, reason, &caller, affected);
+
+        RecallEvent {
+            recall_id: recall.id,
+            issued_by: caller,
+            affected_count,
+        }.publish(&env);
+
+        recall.id
+    }
+
+    pub fn get_recall(env: Env, recall_id: u64) -> Option<Recall> {
+        Recalls::get(&env, recall_id)
+    }
+
+    pub fn get_processor_token_count(env: Env, processor: Address) -> u32 {
+        Recalls::processor_token_count(&env, &processor)
+    }
+
+    pub fn get_processor_tokens(env: Env, processor: Address, page: u32) -> Vec<u128> {
+        Recalls::get_processor_tokens(&env, &processor, page)
+    }
+
+    pub fn issue_license(env: Env, caller: Address, account: Address, license: License) {
+        caller.require_auth();
+        SeedNFT::require_regulator(&env, &caller);
+        Licenses::store(&env, &account, &license);
+        LicenseIssuedEvent {
+            account,
+            number: license.number,
+            license_type: license.license_type,
+            expires_at: license.expires_at,
+        }.publish(&env);
+    }
+
+    pub fn set_license_status(
+        env: Env,
+        caller: Address,
+        account: Address,
+        license_type: LicenseType,
+        status: LicenseStatus,
+    ) {
+        caller.require_auth();
+        SeedNFT::require_regulator(&env, &caller);
+        let mut license = Licenses::get(&env, &account, license_type)
+            .unwrap_or_else(|| panic!("License not found"));
+        license.status = status;
+        Licenses::store(&env, &account, &license);
+        LicenseStatusEvent { account, license_type, status }.publish(&env);
+    }
+
+    pub fn get_license(env: Env, account: Address, license_type: LicenseType) -> Option<License> {
+        Licenses::get(&env, &account, license_type)
+    }
+
+    pub fn has_active_license(env: Env, account: Address, license_type: LicenseType) -> bool {
+        Licenses::is_active(&env, &account, license_type)
+    }
+
+    pub fn set_registry(env: Env, caller: Address, registry: Address) {
+        caller.require_auth();
+        SeedNFT::require_role(&env, &caller, ROLE_ADMIN);
+        Recalls::set_registry(&env, &registry);
+    }
+
+    pub fn get_registry(env: Env) -> Option<Address> {
+        Recalls::get_registry(&env)
+    }
+
+    /// Hands the registry the next token id, so registered seeds never share
+    /// an id with tokens the contract allocates itself.
+    pub fn reserve_token_id(env: Env) -> u128 {
+        Self::require_registry(&env);
+        SeedNFT::next_token_id(&env)
+    }
+
+    pub fn get_compliance_record(env: Env, caller: Address, token_id: u128) -> ComplianceRecord {
+        caller.require_auth();
+        SeedNFT::require_regulator(&env, &caller);
+
+        let metadata = SeedNFT::get_metadata(&env, &token_id)
+            .unwrap_or_else(|| panic!("Token not found"));
+
+        ComplianceAccessEvent { token_id, accessed_by: caller }.publish(&env);
+
+        let freeze = Compliance::get_freeze(&env, &token_id);
+        ComplianceRecord {
+            token_id,
+            frozen_by: freeze.as_ref().map(|freeze| freeze.frozen_by.clone()),
+            freeze_reason: freeze.as_ref().map(|freeze| freeze.reason.clone()),
+            frozen_at: freeze.map(|freeze| freeze.frozen_at),
+            consumer: metadata.consumer,
+            lab_results: LabResults::get(&env, &token_id),
+            sales: History::get_sales(&env, &token_id),
+        }
+    }
+
+    pub fn is_frozen(env: Env, token_id: u128) -> bool {
+        Compliance::is_frozen(&env, &token_id)
+    }
+
+    pub fn get_freeze(env: Env, token_id: u128) -> Option<FreezeRecord> {
+        Compliance::get_freeze(&env, &token_id)
+    }
+
+    pub fn set_lab_accreditation(env: Env, caller: Address, lab: Address, expires_at: u64) {
+        caller.require_auth();
+        SeedNFT::require_role(&env, &caller, ROLE_ADMIN);
+        Accreditations::set(&env, &lab, expires_at);
+        LabAccreditationEvent { lab, expires_at }.publish(&env);
+    }
+
+    pub fn get_lab_accreditation(env: Env, lab: Address) -> Option<u64> {
+        Accreditations::get(&env, &lab)
+    }
+
+    pub fn get_lab_results(env: Env, token_id: u128) -> Vec<LabResult> {
+        LabResults::get(&env, &token_id)
+    }
+
+    pub fn get_metadata(env: Env, token_id: u128) -> Option<SeedMetadata> {
+        SeedNFT::get_public_metadata(&env, &token_id)
+    }
+
+    /// Sales are left out; regulators read them through `get_sales` or
+    /// `get_compliance_record`.
+    pub fn get_history(env: Env, token_id: u128) -> Vec<HistoryEntry> {
+        History::get_public_history(&env, &token_id)
+    }
+
+    pub fn get_custody(env: Env, token_id: u128) -> Vec<Custody> {
+        History::get_custody(&env, &token_id)
+    }
+
+    pub fn get_destructions(env: Env, token_id: u128) -> Vec<Destruction> {
+        History::get_destructions(&env, &token_id)
+    }
+
+    pub fn get_border_crossings(env: Env, token_id: u128) -> Vec<BorderCrossing> {
+        History::get_crossings(&env, &token_id)
+    }
+
+    pub fn get_provenance(env: Env, token_id: u128) -> Provenance {
+        Provenances::build(&env, token_id)
+    }
+
+    pub fn get_parent(env: Env, token_id: u128) -> Option<u128> {
+        Lineage::get_parent(&env, &token_id)
+    }
+
+    pub fn get_sales(env: Env, caller: Address, token_id: u128) -> Vec<Sale> {
+        caller.require_auth();
+        SeedNFT::require_regulator(&env, &caller);
+        ComplianceAccessEvent { token_id, accessed_by: caller }.publish(&env);
+        History::get_sales(&env, &token_id)
+    }
+
+    pub fn get_children(env: Env, token_id: u128) -> Vec<u128> {
+        Lineage::get_children(&env, &token_id)
+    }
+
+    pub fn get_inputs(env: Env, token_id: u128) -> Vec<u128> {
+        Lineage::get_inputs(&env, &token_id)
+    }
+
+    pub fn get_mass_balance(env: Env, token_id: u128) -> Option<MassBalance> {
+        Balances::get(&env, &token_id)
+    }
+
+    pub fn add_to_whitelist(
+        env: Env,
+        caller: Address,
+        account: Address,
+        category: WhitelistCategory,
+        expires_at: Option<u64>,
+    ) {
+        caller.require_auth();
+        SeedNFT::add_to_whitelist(&env, &caller, &account, category, expires_at);
+        WhitelistEvent { account, added: true }.publish(&env);
+    }
+
+    pub fn remove_from_whitelist(env: Env, caller: Address, account: Address) {
+        caller.require_auth();
         SeedNFT::remove_from_whitelist(&env, &caller, &account);
         WhitelistEvent { account, added: false }.publish(&env);
     }
 
+    pub fn add_to_whitelist_batch(
+        env: Env,
+        caller: Address,
+        accounts: Vec<Address>,
+        category: WhitelistCategory,
+        expires_at: Option<u64>,
+    ) {
+        caller.require_auth();
+        if accounts.len() > SeedNFT::get_batch_limit(&env) {
+            panic!("Batch size too large");
+        }
+        for account in accounts.iter() {
+            SeedNFT::add_to_whitelist(&env, &caller, &account, category, expires_at);
+            WhitelistEvent { account, added: true }.publish(&env);
+        }
+    }
+
+    pub fn remove_from_whitelist_batch(env: Env, caller: Address, accounts: Vec<Address>) {
+        caller.require_auth();
+        if accounts.len() > SeedNFT::get_batch_limit(&env) {
+            panic!("Batch size too large");
+        }
+        for account in accounts.iter() {
+            SeedNFT::remove_from_whitelist(&env, &caller, &account);
+            WhitelistEvent { account, added: false }.publish(&env);
+        }
+    }
+
+    pub fn list_whitelist(env: Env, category: WhitelistCategory, cursor: u32, limit: u32) -> Vec<Address> {
+        SeedNFT::list_whitelist(&env, category, cursor, limit)
+    }
+
+    pub fn migrate_whitelist(env: Env, caller: Address, accounts: Vec<Address>) {
+        caller.require_auth();
+        if accounts.len() > SeedNFT::get_batch_limit(&env) {
+            panic!("Batch size too large");
+        }
+        SeedNFT::migrate_whitelist(&env, &caller, &accounts);
+    }
+
     pub fn is_whitelisted(env: Env, account: Address) -> bool {
         SeedNFT::is_whitelisted(&env, &account)
     }
 
+    pub fn attest(env: Env, attester: Address, account: Address, attestation: Attestation) {
+        attester.require_auth();
+        SeedNFT::attest(&env, &attester, &account, &attestation);
+        AttestationEvent {
+            account,
+            attester,
+            expires_at: attestation.expires_at,
+        }.publish(&env);
+    }
+
+    pub fn get_whitelist_entry(env: Env, account: Address) -> Option<WhitelistEntry> {
+        SeedNFT::get_whitelist_entry(&env, &account)
+    }
+
+    pub fn get_attestation(env: Env, account: Address) -> Option<Attestation> {
+        SeedNFT::get_attestation(&env, &account)
+    }
+
+    pub fn has_valid_attestation(env: Env, account: Address) -> bool {
+        SeedNFT::has_valid_attestation(&env, &account)
+    }
+
     pub fn grant_role(env: Env, caller: Address, account: Address, role: Symbol) {
         caller.require_auth();
         SeedNFT::require_role(&env, &caller, ROLE_ADMIN);
@@ -321,6 +1838,48 @@ impl SeedNFTContract {
         RoleRevokeEvent { account, role: role_clone }.publish(&env);
     }
 
+    pub fn propose_admin(env: Env, caller: Address, new_admin: Address) {
+        caller.require_auth();
+        Admins::propose_admin(&env, &caller, &new_admin);
+        AdminProposedEvent {
+            current_admin: caller,
+            proposed_admin: new_admin,
+        }.publish(&env);
+    }
+
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        new_admin.require_auth();
+        let previous_admin = Admins::accept_admin(&env, &new_admin);
+        AdminTransferredEvent {
+            previous_admin,
+            new_admin,
+        }.publish(&env);
+    }
+
+    pub fn cancel_admin_transfer(env: Env, caller: Address) {
+        caller.require_auth();
+        Admins::cancel_admin_transfer(&env, &caller);
+    }
+
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        Admins::get_pending_admin(&env).map(|(_, new_admin)| new_admin)
+    }
+
+    pub fn renounce_admin(env: Env, caller: Address, confirmation: Symbol) {
+        caller.require_auth();
+        Admins::renounce_admin(&env, &caller, confirmation);
+        AdminRenouncedEvent { account: caller }.publish(&env);
+    }
+
+    pub fn get_admin_count(env: Env) -> u32 {
+        Admins::admin_count(&env)
+    }
+
+    pub fn sync_admin_count(env: Env, caller: Address, admins: Vec<Address>) {
+        caller.require_auth();
+        Admins::sync_admin_count(&env, &caller, &admins);
+    }
+
     pub fn has_role(env: Env, account: Address, role: Symbol) -> bool {
         SeedNFT::has_role(&env, &account, role)
     }
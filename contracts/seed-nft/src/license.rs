@@ -0,0 +1,78 @@
+use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol};
+use crate::roles::{ROLE_CULTIVATOR, ROLE_DISPENSARY, ROLE_PROCESSOR, ROLE_LAB};
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LicenseType {
+    Cultivation = 0,
+    Processing = 1,
+    Retail = 2,
+    Lab = 3,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LicenseStatus {
+    Active = 0,
+    Suspended = 1,
+    Revoked = 2,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct License {
+    pub number: String,
+    pub license_type: LicenseType,
+    pub jurisdiction: String,
+    pub issued_at: u64,
+    pub expires_at: u64,
+    pub status: LicenseStatus,
+}
+
+pub struct Licenses;
+
+impl Licenses {
+    const LICENSE: Symbol = symbol_short!("LICENSE");
+
+    pub fn license_type_for_role(role: &Symbol) -> Option<LicenseType> {
+        if *role == ROLE_CULTIVATOR {
+            Some(LicenseType::Cultivation)
+        } else if *role == ROLE_PROCESSOR {
+            Some(LicenseType::Processing)
+        } else if *role == ROLE_DISPENSARY {
+            Some(LicenseType::Retail)
+        } else if *role == ROLE_LAB {
+            Some(LicenseType::Lab)
+        } else {
+            None
+        }
+    }
+
+    pub fn store(env: &Env, account: &Address, license: &License) {
+        env.storage()
+            .persistent()
+            .set(&(Self::LICENSE, account.clone(), license.license_type), license);
+    }
+
+    pub fn get(env: &Env, account: &Address, license_type: LicenseType) -> Option<License> {
+        env.storage()
+            .persistent()
+            .get(&(Self::LICENSE, account.clone(), license_type))
+    }
+
+    pub fn is_active(env: &Env, account: &Address, license_type: LicenseType) -> bool {
+        match Self::get(env, account, license_type) {
+            Some(license) => {
+                license.status == LicenseStatus::Active
+                    && license.expires_at > env.ledger().timestamp()
+            }
+            None => false,
+        }
+    }
+
+    pub fn require_active(env: &Env, account: &Address, license_type: LicenseType) {
+        if !Self::is_active(env, account, license_type) {
+            panic!("Missing active license");
+        }
+    }
+}
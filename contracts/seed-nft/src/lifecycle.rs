@@ -11,6 +11,16 @@ pub enum LifecycleState {
     Processed = 5,
     Distributed = 6,
     Consumed = 7,
+    Split = 8,
+    Merged = 9,
+    Destroyed = 10,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConsumedPolicy {
+    Lock = 0,
+    Burn = 1,
 }
 
 impl LifecycleState {
@@ -24,6 +34,9 @@ impl LifecycleState {
             5 => Some(LifecycleState::Processed),
             6 => Some(LifecycleState::Distributed),
             7 => Some(LifecycleState::Consumed),
+            8 => Some(LifecycleState::Split),
+            9 => Some(LifecycleState::Merged),
+            10 => Some(LifecycleState::Destroyed),
             _ => None,
         }
     }
@@ -32,6 +45,16 @@ impl LifecycleState {
         self as u32
     }
 
+    pub fn is_locked(self) -> bool {
+        matches!(
+            self,
+            LifecycleState::Consumed
+                | LifecycleState::Split
+                | LifecycleState::Merged
+                | LifecycleState::Destroyed
+        )
+    }
+
     pub fn can_transition_to(self, to: LifecycleState) -> bool {
         match (self, to) {
             (LifecycleState::Seed, LifecycleState::Germinated) => true,
@@ -41,6 +64,9 @@ impl LifecycleState {
             (LifecycleState::PlantHarvested, LifecycleState::Processed) => true,
             (LifecycleState::Processed, LifecycleState::Distributed) => true,
             (LifecycleState::Distributed, LifecycleState::Consumed) => true,
+            (LifecycleState::PlantHarvested, LifecycleState::Split) => true,
+            (LifecycleState::PlantHarvested, LifecycleState::Merged) => true,
+            (LifecycleState::Processed, LifecycleState::Merged) => true,
             _ => false,
         }
     }
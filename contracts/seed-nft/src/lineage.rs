@@ -0,0 +1,50 @@
+use soroban_sdk::{symbol_short, Env, Symbol, Vec};
+
+pub struct Lineage;
+
+impl Lineage {
+    const PARENT: Symbol = symbol_short!("PARENT");
+    const CHILDREN: Symbol = symbol_short!("CHILDREN");
+    const INPUTS: Symbol = symbol_short!("INPUTS");
+
+    pub fn link(env: &Env, parent_id: &u128, child_id: &u128) {
+        env.storage()
+            .persistent()
+            .set(&(Self::PARENT, child_id), parent_id);
+        Self::add_child(env, parent_id, child_id);
+    }
+
+    pub fn add_child(env: &Env, parent_id: &u128, child_id: &u128) {
+        let mut children = Self::get_children(env, parent_id);
+        children.push_back(*child_id);
+        env.storage()
+            .persistent()
+            .set(&(Self::CHILDREN, parent_id), &children);
+    }
+
+    pub fn get_parent(env: &Env, token_id: &u128) -> Option<u128> {
+        env.storage()
+            .persistent()
+            .get(&(Self::PARENT, token_id))
+    }
+
+    pub fn get_children(env: &Env, token_id: &u128) -> Vec<u128> {
+        env.storage()
+            .persistent()
+            .get(&(Self::CHILDREN, token_id))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    pub fn set_inputs(env: &Env, token_id: &u128, inputs: &Vec<u128>) {
+        env.storage()
+            .persistent()
+            .set(&(Self::INPUTS, token_id), inputs);
+    }
+
+    pub fn get_inputs(env: &Env, token_id: &u128) -> Vec<u128> {
+        env.storage()
+            .persistent()
+            .get(&(Self::INPUTS, token_id))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+}
@@ -18,6 +18,31 @@ pub struct OpenSeaMetadata {
     pub attributes: Option<Vec<Attribute>>,
 }
 
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum QuantityUnit {
+    Milligrams = 0,
+    Grams = 1,
+    Units = 2,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct LotSpec {
+    pub product_type: String,
+    pub quantity: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ProductSpec {
+    pub name: String,
+    pub description: String,
+    pub image: String,
+    pub product_type: String,
+    pub quantity: u64,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct SeedMetadata {
@@ -25,10 +50,13 @@ pub struct SeedMetadata {
     pub location: Option<String>,
     pub temperature: Option<i32>,
     pub humidity: Option<u32>,
-    pub lab_analysis: Option<String>,
     pub processor: Option<Address>,
     pub distributor: Option<Address>,
     pub consumer: Option<Address>,
+    pub parent: Option<u128>,
+    pub product_type: Option<String>,
+    pub quantity: Option<u64>,
+    pub expires_at: Option<u64>,
     pub updated_at: u64,
     pub name: String,
     pub description: String,
@@ -1,13 +1,34 @@
-use soroban_sdk::{symbol_short, Address, Env, Symbol};
+use soroban_sdk::{panic_with_error, symbol_short, Address, Env, Symbol, TryFromVal, Val, Vec};
+use seed_common::admin::Admins;
+use crate::border::Borders;
+use crate::compliance::Compliance;
+use crate::Error;
+use crate::license::Licenses;
+use crate::lifecycle::{ConsumedPolicy, LifecycleState};
 use crate::metadata::SeedMetadata;
-use crate::roles::{ROLE_ADMIN, get_role_key};
+use crate::roles::{ROLE_ADMIN, ROLE_ATTESTER, ROLE_REGULATOR};
+use crate::transfers::TransferRules;
+use crate::whitelist::{Attestation, WhitelistCategory, WhitelistEntry};
 
 pub struct SeedNFT;
 
 impl SeedNFT {
     const METADATA: Symbol = symbol_short!("METADATA");
     const WHITELIST: Symbol = symbol_short!("WHITELIST");
+    const WL_INDEX: Symbol = symbol_short!("WL_INDEX");
+    const WL_COUNT: Symbol = symbol_short!("WL_COUNT");
+    const WL_POS: Symbol = symbol_short!("WL_POS");
+    const ATTEST: Symbol = symbol_short!("ATTEST");
     const PAUSED: Symbol = symbol_short!("PAUSED");
+    const NEXT_ID: Symbol = symbol_short!("NEXT_ID");
+    const BATCH_LIM: Symbol = symbol_short!("BATCH_LIM");
+    const DEFAULT_BATCH_LIMIT: u32 = 100;
+    const SHELF_LIFE: Symbol = symbol_short!("SHELFLIFE");
+    const EXP_INDEX: Symbol = symbol_short!("EXP_INDEX");
+    const EXP_COUNT: Symbol = symbol_short!("EXP_COUNT");
+    const EXP_POS: Symbol = symbol_short!("EXP_POS");
+    const DEFAULT_SHELF_LIFE: u64 = 365 * 24 * 60 * 60;
+    const CONSUMED: Symbol = symbol_short!("CONSUMED");
 
     pub fn require_not_paused(env: &Env) {
         let paused: bool = env.storage().instance().get(&Self::PAUSED).unwrap_or(false);
@@ -16,36 +37,50 @@ impl SeedNFT {
         }
     }
 
+    pub fn require_transferable(env: &Env, token_id: u32) {
+        let token_id = token_id as u128;
+        Self::require_not_frozen(env, &token_id);
+        if let Some(metadata) = Self::get_metadata(env, &token_id) {
+            if metadata.state.is_locked() {
+                panic!("Token is locked");
+            }
+        }
+    }
+
+    pub fn require_not_frozen(env: &Env, token_id: &u128) {
+        if Compliance::is_frozen(env, token_id) {
+            panic_with_error!(env, Error::TokenFrozen);
+        }
+    }
+
     pub fn require_role(env: &Env, account: &Address, role: Symbol) {
-        let role_key = get_role_key(role);
-        let has_role: bool = env
-            .storage()
-            .persistent()
-            .get(&(role_key, account.clone()))
-            .unwrap_or(false);
-        if !has_role {
+        Admins::require_role(env, account, role);
+    }
+
+    pub fn require_licensed_role(env: &Env, account: &Address, role: Symbol) {
+        let license_type = Licenses::license_type_for_role(&role);
+        Self::require_role(env, account, role);
+        if let Some(license_type) = license_type {
+            Licenses::require_active(env, account, license_type);
+        }
+    }
+
+    pub fn require_regulator(env: &Env, account: &Address) {
+        if !Self::has_role(env, account, ROLE_ADMIN) && !Self::has_role(env, account, ROLE_REGULATOR) {
             panic!("Missing required role");
         }
     }
 
     pub fn grant_role(env: &Env, account: &Address, role: Symbol) {
-        let role_key = get_role_key(role);
-        env.storage()
-            .persistent()
-            .set(&(role_key, account.clone()), &true);
+        Admins::grant_role(env, account, role);
     }
 
     pub fn revoke_role(env: &Env, account: &Address, role: Symbol) {
-        let role_key = get_role_key(role);
-        env.storage().persistent().remove(&(role_key, account.clone()));
+        Admins::revoke_role(env, account, role);
     }
 
     pub fn has_role(env: &Env, account: &Address, role: Symbol) -> bool {
-        let role_key = get_role_key(role);
-        env.storage()
-            .persistent()
-            .get(&(role_key, account.clone()))
-            .unwrap_or(false)
+        Admins::has_role(env, account, role)
     }
 
     pub fn pause(env: &Env, account: &Address) {
@@ -58,6 +93,127 @@ impl SeedNFT {
         env.storage().instance().set(&Self::PAUSED, &false);
     }
 
+    pub fn set_batch_limit(env: &Env, account: &Address, limit: u32) {
+        Self::require_role(env, account, ROLE_ADMIN);
+        if limit == 0 {
+            panic!("Invalid batch limit");
+        }
+        env.storage().instance().set(&Self::BATCH_LIM, &limit);
+    }
+
+    pub fn get_batch_limit(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&Self::BATCH_LIM)
+            .unwrap_or(Self::DEFAULT_BATCH_LIMIT)
+    }
+
+    pub fn set_shelf_life(env: &Env, account: &Address, seconds: u64) {
+        Self::require_role(env, account, ROLE_ADMIN);
+        if seconds == 0 {
+            panic!("Invalid shelf life");
+        }
+        env.storage().instance().set(&Self::SHELF_LIFE, &seconds);
+    }
+
+    pub fn get_shelf_life(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&Self::SHELF_LIFE)
+            .unwrap_or(Self::DEFAULT_SHELF_LIFE)
+    }
+
+    pub fn start_shelf_life(env: &Env, token_id: &u128, metadata: &mut SeedMetadata) {
+        let expires_at = env.ledger().timestamp().saturating_add(Self::get_shelf_life(env));
+        metadata.expires_at = Some(expires_at);
+        Self::track_expiry(env, token_id);
+    }
+
+    /// Adds a token to the expiring index, which holds every token with a
+    /// shelf life that is still in stock.
+    pub fn track_expiry(env: &Env, token_id: &u128) {
+        if env.storage().persistent().has(&(Self::EXP_POS, *token_id)) {
+            return;
+        }
+        let slot = Self::expiring_len(env);
+        env.storage()
+            .persistent()
+            .set(&(Self::EXP_INDEX, slot), token_id);
+        env.storage()
+            .persistent()
+            .set(&(Self::EXP_POS, *token_id), &slot);
+        env.storage().persistent().set(&Self::EXP_COUNT, &(slot + 1));
+    }
+
+    /// Drops a token from the expiring index once it has left stock, moving
+    /// the last slot into the freed one.
+    pub fn end_shelf_life(env: &Env, token_id: &u128, metadata: &SeedMetadata) {
+        if metadata.expires_at.is_none() {
+            return;
+        }
+        let slot: u32 = match env.storage().persistent().get(&(Self::EXP_POS, *token_id)) {
+            Some(slot) => slot,
+            None => return,
+        };
+        let last = Self::expiring_len(env) - 1;
+        if slot != last {
+            let moved: u128 = env
+                .storage()
+                .persistent()
+                .get(&(Self::EXP_INDEX, last))
+                .unwrap();
+            env.storage()
+                .persistent()
+                .set(&(Self::EXP_INDEX, slot), &moved);
+            env.storage()
+                .persistent()
+                .set(&(Self::EXP_POS, moved), &slot);
+        }
+        env.storage().persistent().remove(&(Self::EXP_INDEX, last));
+        env.storage().persistent().remove(&(Self::EXP_POS, *token_id));
+        env.storage().persistent().set(&Self::EXP_COUNT, &last);
+    }
+
+    pub fn expiring_len(env: &Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Self::EXP_COUNT)
+            .unwrap_or(0)
+    }
+
+    pub fn get_expiring(env: &Env, cursor: u32, limit: u32) -> Vec<u128> {
+        let end = cursor.saturating_add(limit).min(Self::expiring_len(env));
+        let mut result = Vec::new(env);
+        for slot in cursor..end {
+            let token_id: u128 = env
+                .storage()
+                .persistent()
+                .get(&(Self::EXP_INDEX, slot))
+                .unwrap();
+            result.push_back(token_id);
+        }
+        result
+    }
+
+    pub fn is_expired(env: &Env, metadata: &SeedMetadata) -> bool {
+        match metadata.expires_at {
+            Some(expires_at) => env.ledger().timestamp() >= expires_at,
+            None => false,
+        }
+    }
+
+    pub fn set_consumed_policy(env: &Env, account: &Address, policy: ConsumedPolicy) {
+        Self::require_role(env, account, ROLE_ADMIN);
+        env.storage().instance().set(&Self::CONSUMED, &policy);
+    }
+
+    pub fn get_consumed_policy(env: &Env) -> ConsumedPolicy {
+        env.storage()
+            .instance()
+            .get(&Self::CONSUMED)
+            .unwrap_or(ConsumedPolicy::Lock)
+    }
+
     pub fn store_metadata(env: &Env, token_id: &u128, metadata: &SeedMetadata) {
         env.storage()
             .persistent()
@@ -70,23 +226,255 @@ impl SeedNFT {
             .get(&(Self::METADATA, token_id))
     }
 
-    pub fn add_to_whitelist(env: &Env, caller: &Address, account: &Address) {
+    /// Metadata as shown to anyone; the consumer stays behind the
+    /// regulator-gated compliance record.
+    pub fn get_public_metadata(env: &Env, token_id: &u128) -> Option<SeedMetadata> {
+        Self::get_metadata(env, token_id).map(|mut metadata| {
+            metadata.consumer = None;
+            metadata
+        })
+    }
+
+    pub fn track_token_id(env: &Env, token_id: &u128) {
+        let next: u128 = env.storage().instance().get(&Self::NEXT_ID).unwrap_or(1);
+        if *token_id >= next {
+            env.storage().instance().set(&Self::NEXT_ID, &(token_id + 1));
+        }
+    }
+
+    pub fn next_token_id(env: &Env) -> u128 {
+        let next: u128 = env.storage().instance().get(&Self::NEXT_ID).unwrap_or(1);
+        env.storage().instance().set(&Self::NEXT_ID, &(next + 1));
+        next
+    }
+
+    pub fn add_to_whitelist(
+        env: &Env,
+        caller: &Address,
+        account: &Address,
+        category: WhitelistCategory,
+        expires_at: Option<u64>,
+    ) {
         Self::require_role(env, caller, ROLE_ADMIN);
-        env.storage()
-            .persistent()
-            .set(&(Self::WHITELIST, account.clone()), &true);
+        let entry = WhitelistEntry {
+            category,
+            expires_at,
+        };
+        Self::store_whitelist_entry(env, account, &entry);
     }
 
     pub fn remove_from_whitelist(env: &Env, caller: &Address, account: &Address) {
         Self::require_role(env, caller, ROLE_ADMIN);
+        if let Some(entry) = Self::get_whitelist_entry(env, account) {
+            Self::unindex(env, entry.category, account);
+        }
         env.storage().persistent().remove(&(Self::WHITELIST, account.clone()));
+        env.storage().persistent().remove(&(Self::ATTEST, account.clone()));
     }
 
     pub fn is_whitelisted(env: &Env, account: &Address) -> bool {
+        match Self::get_whitelist_entry(env, account) {
+            Some(entry) => match entry.expires_at {
+                Some(expires_at) => expires_at > env.ledger().timestamp(),
+                None => true,
+            },
+            None => false,
+        }
+    }
+
+    /// Pages through the slots of `category`, skipping expired entries, so a
+    /// page may hold fewer than `limit` accounts.
+    pub fn list_whitelist(env: &Env, category: WhitelistCategory, cursor: u32, limit: u32) -> Vec<Address> {
+        let end = cursor.saturating_add(limit).min(Self::index_len(env, category));
+        let mut result = Vec::new(env);
+        for slot in cursor..end {
+            let account: Address = env
+                .storage()
+                .persistent()
+                .get(&(Self::WL_INDEX, category, slot))
+                .unwrap();
+            if Self::is_whitelisted(env, &account) {
+                result.push_back(account);
+            }
+        }
+        result
+    }
+
+    /// Re-stores entries written before whitelist categories existed, when
+    /// the value was a plain `true`, so they are indexed and typed.
+    pub fn migrate_whitelist(env: &Env, caller: &Address, accounts: &Vec<Address>) {
+        Self::require_role(env, caller, ROLE_ADMIN);
+        for account in accounts.iter() {
+            let indexed = env.storage().persistent().has(&(Self::WL_POS, account.clone()));
+            if let Some(entry) = Self::get_whitelist_entry(env, &account) {
+                if !indexed {
+                    Self::store_whitelist_entry(env, &account, &entry);
+                }
+            }
+        }
+    }
+
+    pub fn attest(env: &Env, attester: &Address, account: &Address, attestation: &Attestation) {
+        Self::require_role(env, attester, ROLE_ATTESTER);
+        if !Self::is_whitelisted(env, account) {
+            panic!("Account not whitelisted");
+        }
+        if attestation.expires_at <= env.ledger().timestamp() {
+            panic!("Attestation already expired");
+        }
+
+        let mut attestation = attestation.clone();
+        attestation.attester = attester.clone();
+        env.storage()
+            .persistent()
+            .set(&(Self::ATTEST, account.clone()), &attestation);
+    }
+
+    pub fn get_attestation(env: &Env, account: &Address) -> Option<Attestation> {
+        env.storage()
+            .persistent()
+            .get(&(Self::ATTEST, account.clone()))
+    }
+
+    /// An attestation only counts while the whitelist entry it was attached to
+    /// is still valid.
+    pub fn has_valid_attestation(env: &Env, account: &Address) -> bool {
+        if !Self::is_whitelisted(env, account) {
+            return false;
+        }
+        match Self::get_attestation(env, account) {
+            Some(attestation) => attestation.expires_at > env.ledger().timestamp(),
+            None => false,
+        }
+    }
+
+    /// Runs just before a transfer, which uses up the export permit when the
+    /// token leaves its jurisdiction.
+    pub fn require_recipient_allowed(env: &Env, to: &Address, token_id: u32) {
+        let token_id = token_id as u128;
+        match Self::get_metadata(env, &token_id) {
+            Some(metadata) => {
+                Self::require_recipient_for(env, to, metadata.state, None);
+                if let Err(error) = Borders::authorize_transfer(env, &token_id, to) {
+                    panic_with_error!(env, error);
+                }
+            }
+            None => {
+                if !Self::is_whitelisted(env, to) {
+                    panic!("Recipient not whitelisted");
+                }
+            }
+        }
+    }
+
+    /// Checks that `to` may receive a token in `state` derived from `source`,
+    /// so tokens minted from an existing one face the same rules as a transfer
+    /// and stay in the source's jurisdiction.
+    pub fn require_recipient_for(env: &Env, to: &Address, state: LifecycleState, source: Option<&u128>) {
+        if !Self::is_whitelisted(env, to) {
+            panic!("Recipient not whitelisted");
+        }
+        if state == LifecycleState::Distributed && Self::requires_attestation(env, to) {
+            panic_with_error!(env, Error::MissingAttestation);
+        }
+        if let Err(error) = TransferRules::check(env, state, to) {
+            panic_with_error!(env, error);
+        }
+        if let Some(source) = source {
+            if let Err(error) = Borders::check_domestic(env, source, to) {
+                panic_with_error!(env, error);
+            }
+        }
+    }
+
+    /// Only patients and consumers need an attestation to receive retail
+    /// stock; dispensaries restocking each other do not.
+    fn requires_attestation(env: &Env, account: &Address) -> bool {
+        let retail = match Self::get_whitelist_entry(env, account) {
+            Some(entry) => matches!(entry.category, WhitelistCategory::Patient | WhitelistCategory::Consumer),
+            None => false,
+        };
+        retail && !Self::has_valid_attestation(env, account)
+    }
+
+    /// Legacy `true` flags read as open-ended Business entries, the widest
+    /// category before distribution.
+    pub fn get_whitelist_entry(env: &Env, account: &Address) -> Option<WhitelistEntry> {
+        let value: Val = env
+            .storage()
+            .persistent()
+            .get(&(Self::WHITELIST, account.clone()))?;
+        if let Ok(listed) = bool::try_from_val(env, &value) {
+            return listed.then_some(WhitelistEntry {
+                category: WhitelistCategory::Business,
+                expires_at: None,
+            });
+        }
+        Some(WhitelistEntry::try_from_val(env, &value).unwrap())
+    }
+
+    fn store_whitelist_entry(env: &Env, account: &Address, entry: &WhitelistEntry) {
+        let indexed = env.storage().persistent().has(&(Self::WL_POS, account.clone()));
+        let previous = Self::get_whitelist_entry(env, account);
+        match previous {
+            Some(previous) if indexed && previous.category == entry.category => {}
+            Some(previous) if indexed => {
+                Self::unindex(env, previous.category, account);
+                Self::index(env, entry.category, account);
+            }
+            _ => Self::index(env, entry.category, account),
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(Self::WHITELIST, account.clone()), entry);
+    }
+
+    fn index_len(env: &Env, category: WhitelistCategory) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(Self::WL_COUNT, category))
+            .unwrap_or(0)
+    }
+
+    fn index(env: &Env, category: WhitelistCategory, account: &Address) {
+        let slot = Self::index_len(env, category);
+        env.storage()
+            .persistent()
+            .set(&(Self::WL_INDEX, category, slot), account);
+        env.storage()
+            .persistent()
+            .set(&(Self::WL_POS, account.clone()), &slot);
+        env.storage()
+            .persistent()
+            .set(&(Self::WL_COUNT, category), &(slot + 1));
+    }
+
+    /// Moves the last slot into the freed one so removal stays constant time.
+    fn unindex(env: &Env, category: WhitelistCategory, account: &Address) {
+        let slot: u32 = match env.storage().persistent().get(&(Self::WL_POS, account.clone())) {
+            Some(slot) => slot,
+            None => return,
+        };
+        let last = Self::index_len(env, category) - 1;
+        if slot != last {
+            let moved: Address = env
+                .storage()
+                .persistent()
+                .get(&(Self::WL_INDEX, category, last))
+                .unwrap();
+            env.storage()
+                .persistent()
+                .set(&(Self::WL_INDEX, category, slot), &moved);
+            env.storage()
+                .persistent()
+                .set(&(Self::WL_POS, moved), &slot);
+        }
+        env.storage().persistent().remove(&(Self::WL_INDEX, category, last));
+        env.storage().persistent().remove(&(Self::WL_POS, account.clone()));
         env.storage()
             .persistent()
-            .get(&(Self::WHITELIST, account.clone()))
-            .unwrap_or(false)
+            .set(&(Self::WL_COUNT, category), &last);
     }
 }
 
@@ -0,0 +1,113 @@
+use soroban_sdk::{contracttype, vec, Address, Env, IntoVal, String, Symbol, Vec};
+use seed_common::seed_data::SeedData;
+use crate::compliance::Compliance;
+use crate::history::{History, HistoryEntry};
+use crate::lab::{LabResult, LabResults};
+use crate::lineage::Lineage;
+use crate::metadata::SeedMetadata;
+use crate::nft::SeedNFT;
+use crate::recall::Recalls;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct TokenRecord {
+    pub token_id: u128,
+    pub metadata: SeedMetadata,
+    pub history: Vec<HistoryEntry>,
+    pub lab_results: Vec<LabResult>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ComplianceFlags {
+    pub frozen: bool,
+    pub frozen_by: Option<Address>,
+    pub freeze_reason: Option<String>,
+    pub expired: bool,
+    pub locked: bool,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Provenance {
+    pub token: TokenRecord,
+    pub ancestors: Vec<TokenRecord>,
+    pub origin_seeds: Vec<SeedData>,
+    pub compliance: ComplianceFlags,
+}
+
+pub struct Provenances;
+
+impl Provenances {
+    pub fn build(env: &Env, token_id: u128) -> Provenance {
+        let token = Self::record(env, token_id);
+
+        let mut ancestors: Vec<TokenRecord> = Vec::new(env);
+        let mut visited: Vec<u128> = vec![env, token_id];
+        let mut roots: Vec<u128> = Vec::new(env);
+        let mut pending = vec![env, token_id];
+        while let Some(current) = pending.pop_front() {
+            let sources = Self::sources(env, current);
+            if sources.is_empty() {
+                roots.push_back(current);
+            }
+            for source in sources.iter() {
+                if visited.contains(source) {
+                    continue;
+                }
+                visited.push_back(source);
+                ancestors.push_back(Self::record(env, source));
+                pending.push_back(source);
+            }
+        }
+
+        let mut origin_seeds = Vec::new(env);
+        if let Some(registry) = Recalls::get_registry(env) {
+            for root in roots.iter() {
+                let seed: Option<SeedData> = env.invoke_contract(
+                    &registry,
+                    &Symbol::new(env, "get_seed_by_nft_id"),
+                    vec![env, root.into_val(env)],
+                );
+                if let Some(seed) = seed {
+                    origin_seeds.push_back(seed);
+                }
+            }
+        }
+
+        let freeze = Compliance::get_freeze(env, &token_id);
+        let compliance = ComplianceFlags {
+            frozen: freeze.is_some(),
+            frozen_by: freeze.as_ref().map(|freeze| freeze.frozen_by.clone()),
+            freeze_reason: freeze.map(|freeze| freeze.reason),
+            expired: SeedNFT::is_expired(env, &token.metadata),
+            locked: token.metadata.state.is_locked(),
+        };
+
+        Provenance {
+            token,
+            ancestors,
+            origin_seeds,
+            compliance,
+        }
+    }
+
+    fn record(env: &Env, token_id: u128) -> TokenRecord {
+        let metadata = SeedNFT::get_public_metadata(env, &token_id)
+            .unwrap_or_else(|| panic!("Token not found"));
+        TokenRecord {
+            token_id,
+            metadata,
+            history: History::get_public_history(env, &token_id),
+            lab_results: LabResults::get(env, &token_id),
+        }
+    }
+
+    fn sources(env: &Env, token_id: u128) -> Vec<u128> {
+        let mut sources = Lineage::get_inputs(env, &token_id);
+        if let Some(parent) = Lineage::get_parent(env, &token_id) {
+            sources.push_front(parent);
+        }
+        sources
+    }
+}
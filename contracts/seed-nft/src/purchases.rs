@@ -0,0 +1,150 @@
+use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol, Vec};
+use crate::metadata::QuantityUnit;
+use crate::Error;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PurchaseLimit {
+    pub category: String,
+    pub unit: QuantityUnit,
+    pub max_quantity: u64,
+    pub window: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Purchase {
+    pub category: String,
+    pub unit: QuantityUnit,
+    pub quantity: u64,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Allowance {
+    pub category: String,
+    pub unit: QuantityUnit,
+    pub limit: u64,
+    pub purchased: u64,
+    pub remaining: u64,
+}
+
+pub struct Purchases;
+
+impl Purchases {
+    const LIMITS: Symbol = symbol_short!("LIMITS");
+    const PURCHASES: Symbol = symbol_short!("PURCHASES");
+
+    pub fn set_limit(env: &Env, limit: &PurchaseLimit) {
+        let mut limits = Self::get_limits(env);
+        if let Some(index) = Self::position(&limits, &limit.category, limit.unit) {
+            limits.set(index, limit.clone());
+        } else {
+            limits.push_back(limit.clone());
+        }
+        env.storage().instance().set(&Self::LIMITS, &limits);
+    }
+
+    pub fn remove_limit(env: &Env, category: &String, unit: QuantityUnit) {
+        let mut limits = Self::get_limits(env);
+        let index = Self::position(&limits, category, unit)
+            .unwrap_or_else(|| panic!("Purchase limit not found"));
+        limits.remove(index);
+        env.storage().instance().set(&Self::LIMITS, &limits);
+    }
+
+    pub fn get_limits(env: &Env) -> Vec<PurchaseLimit> {
+        env.storage()
+            .instance()
+            .get(&Self::LIMITS)
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    pub fn record(
+        env: &Env,
+        consumer: &Address,
+        category: &String,
+        unit: QuantityUnit,
+        quantity: u64,
+    ) -> Result<(), Error> {
+        let now = env.ledger().timestamp();
+        let limits = Self::get_limits(env);
+        let mut purchases = Self::get_recent(env, consumer, &limits);
+
+        if let Some(index) = Self::position(&limits, category, unit) {
+            let limit = limits.get(index).unwrap();
+            let purchased = Self::total_within(&purchases, &limit, now);
+            if purchased.saturating_add(quantity) > limit.max_quantity {
+                return Err(Error::PurchaseLimitExceeded);
+            }
+        }
+
+        purchases.push_back(Purchase {
+            category: category.clone(),
+            unit,
+            quantity,
+            timestamp: now,
+        });
+        env.storage()
+            .persistent()
+            .set(&(Self::PURCHASES, consumer.clone()), &purchases);
+        Ok(())
+    }
+
+    pub fn remaining_allowance(env: &Env, consumer: &Address) -> Vec<Allowance> {
+        let now = env.ledger().timestamp();
+        let limits = Self::get_limits(env);
+        let purchases = Self::get_recent(env, consumer, &limits);
+        let mut allowances = Vec::new(env);
+
+        for limit in limits.iter() {
+            let purchased = Self::total_within(&purchases, &limit, now);
+            allowances.push_back(Allowance {
+                category: limit.category.clone(),
+                unit: limit.unit,
+                limit: limit.max_quantity,
+                purchased,
+                remaining: limit.max_quantity.saturating_sub(purchased),
+            });
+        }
+
+        allowances
+    }
+
+    fn get_recent(env: &Env, consumer: &Address, limits: &Vec<PurchaseLimit>) -> Vec<Purchase> {
+        let now = env.ledger().timestamp();
+        let max_window = limits.iter().map(|l| l.window).max().unwrap_or(0);
+        let purchases: Vec<Purchase> = env
+            .storage()
+            .persistent()
+            .get(&(Self::PURCHASES, consumer.clone()))
+            .unwrap_or_else(|| Vec::new(env));
+
+        let mut recent = Vec::new(env);
+        for purchase in purchases.iter() {
+            if purchase.timestamp.saturating_add(max_window) > now {
+                recent.push_back(purchase);
+            }
+        }
+        recent
+    }
+
+    fn total_within(purchases: &Vec<Purchase>, limit: &PurchaseLimit, now: u64) -> u64 {
+        purchases
+            .iter()
+            .filter(|p| {
+                p.category == limit.category
+                    && p.unit == limit.unit
+                    && p.timestamp.saturating_add(limit.window) > now
+            })
+            .fold(0u64, |total, p| total.saturating_add(p.quantity))
+    }
+
+    fn position(limits: &Vec<PurchaseLimit>, category: &String, unit: QuantityUnit) -> Option<u32> {
+        limits
+            .iter()
+            .position(|l| l.category == *category && l.unit == unit)
+            .map(|index| index as u32)
+    }
+}
@@ -0,0 +1,156 @@
+use soroban_sdk::{
+    contracttype, symbol_short, vec, Address, Env, IntoVal, Map, String, Symbol, Vec,
+};
+use crate::lineage::Lineage;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RecallScope {
+    Batch(String),
+    SeedBank(String),
+    SeedId(String),
+    /// Every token the processor produced, across all index pages.
+    Processor(Address),
+    Token(u128),
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Recall {
+    pub id: u64,
+    pub scope: RecallScope,
+    pub reason: String,
+    pub issued_by: Address,
+    pub issued_at: u64,
+    pub affected: Vec<u128>,
+}
+
+pub struct Recalls;
+
+impl Recalls {
+    const RECALL: Symbol = symbol_short!("RECALL");
+    const RECALL_CNT: Symbol = symbol_short!("RECALLCNT");
+    const REGISTRY: Symbol = symbol_short!("REGISTRY");
+    const PROC_TOK: Symbol = symbol_short!("PROC_TOK");
+    const PROC_CNT: Symbol = symbol_short!("PROC_CNT");
+    pub const PROC_PAGE: u32 = 100;
+
+    pub fn set_registry(env: &Env, registry: &Address) {
+        env.storage().instance().set(&Self::REGISTRY, registry);
+    }
+
+    pub fn get_registry(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&Self::REGISTRY)
+    }
+
+    pub fn index_processor(env: &Env, processor: &Address, token_id: &u128) {
+        let count = Self::processor_token_count(env, processor);
+        let page = count / Self::PROC_PAGE;
+
+        let mut tokens = Self::get_processor_tokens(env, processor, page);
+        tokens.push_back(*token_id);
+        env.storage()
+            .persistent()
+            .set(&(Self::PROC_TOK, processor.clone(), page), &tokens);
+        env.storage()
+            .persistent()
+            .set(&(Self::PROC_CNT, processor.clone()), &(count + 1));
+    }
+
+    pub fn processor_token_count(env: &Env, processor: &Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(Self::PROC_CNT, processor.clone()))
+            .unwrap_or(0)
+    }
+
+    pub fn get_processor_tokens(env: &Env, processor: &Address, page: u32) -> Vec<u128> {
+        env.storage()
+            .persistent()
+            .get(&(Self::PROC_TOK, processor.clone(), page))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    pub fn resolve(env: &Env, scope: &RecallScope) -> Vec<u128> {
+        let roots = match scope {
+            RecallScope::Batch(batch) => Self::query_registry(
+                env,
+                "get_nft_ids_by_batch",
+                batch,
+            ),
+            RecallScope::SeedBank(seed_bank) => Self::query_registry(
+                env,
+                "get_nft_ids_by_seed_bank",
+                seed_bank,
+            ),
+            RecallScope::SeedId(seed_id) => Self::query_registry(
+                env,
+                "get_nft_ids_by_seed_id",
+                seed_id,
+            ),
+            RecallScope::Processor(processor) => {
+                let pages = Self::processor_token_count(env, processor).div_ceil(Self::PROC_PAGE);
+                let mut tokens = Vec::new(env);
+                for page in 0..pages {
+                    tokens.append(&Self::get_processor_tokens(env, processor, page));
+                }
+                tokens
+            }
+            RecallScope::Token(token_id) => vec![env, *token_id],
+        };
+
+        let mut affected: Vec<u128> = Vec::new(env);
+        let mut seen: Map<u128, bool> = Map::new(env);
+        let mut pending = roots;
+        while let Some(token_id) = pending.pop_front() {
+            if seen.contains_key(token_id) {
+                continue;
+            }
+            seen.set(token_id, true);
+            affected.push_back(token_id);
+            pending.append(&Lineage::get_children(env, &token_id));
+        }
+
+        affected
+    }
+
+    pub fn store(
+        env: &Env,
+        scope: RecallScope,
+        reason: String,
+        issued_by: &Address,
+        affected: Vec<u128>,
+    ) -> Recall {
+        let id: u64 = env.storage().instance().get(&Self::RECALL_CNT).unwrap_or(0) + 1;
+        env.storage().instance().set(&Self::RECALL_CNT, &id);
+
+        let recall = Recall {
+            id,
+            scope,
+            reason,
+            issued_by: issued_by.clone(),
+            issued_at: env.ledger().timestamp(),
+            affected,
+        };
+        env.storage()
+            .persistent()
+            .set(&(Self::RECALL, id), &recall);
+        recall
+    }
+
+    pub fn get(env: &Env, recall_id: u64) -> Option<Recall> {
+        env.storage()
+            .persistent()
+            .get(&(Self::RECALL, recall_id))
+    }
+
+    fn query_registry(env: &Env, function: &str, value: &String) -> Vec<u128> {
+        let registry = Self::get_registry(env)
+            .unwrap_or_else(|| panic!("Registry not configured"));
+        env.invoke_contract(
+            &registry,
+            &Symbol::new(env, function),
+            vec![env, value.into_val(env)],
+        )
+    }
+}
@@ -0,0 +1,76 @@
+use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol};
+use crate::lab::LabResults;
+use crate::license::{LicenseType, Licenses};
+use crate::lifecycle::LifecycleState;
+use crate::metadata::SeedMetadata;
+use crate::nft::SeedNFT;
+use crate::roles::ROLE_ADMIN;
+use crate::Error;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StateRequirements {
+    pub location: bool,
+    pub quantity: bool,
+    pub lab_analysis: bool,
+    pub licensed_holder: bool,
+}
+
+pub struct Requirements;
+
+impl Requirements {
+    const REQS: Symbol = symbol_short!("REQS");
+
+    pub fn set(env: &Env, caller: &Address, state: LifecycleState, requirements: &StateRequirements) {
+        SeedNFT::require_role(env, caller, ROLE_ADMIN);
+        env.storage()
+            .persistent()
+            .set(&(Self::REQS, state), requirements);
+    }
+
+    pub fn get(env: &Env, state: LifecycleState) -> StateRequirements {
+        env.storage()
+            .persistent()
+            .get(&(Self::REQS, state))
+            .unwrap_or_else(|| Self::default_for(state))
+    }
+
+    pub fn check(
+        env: &Env,
+        state: LifecycleState,
+        token_id: &u128,
+        metadata: &SeedMetadata,
+        location: &Option<String>,
+        holder: &Address,
+    ) -> Result<(), Error> {
+        let requirements = Self::get(env, state);
+
+        if requirements.location && location.is_none() {
+            return Err(Error::MissingLocation);
+        }
+        if requirements.quantity && metadata.quantity.is_none() {
+            return Err(Error::MissingQuantity);
+        }
+        if requirements.lab_analysis {
+            match LabResults::latest(env, token_id) {
+                None => return Err(Error::MissingLabAnalysis),
+                Some(result) if !result.passed => return Err(Error::FailedLabAnalysis),
+                Some(_) => {}
+            }
+        }
+        if requirements.licensed_holder && !Licenses::is_active(env, holder, LicenseType::Retail) {
+            return Err(Error::UnlicensedDestination);
+        }
+
+        Ok(())
+    }
+
+    fn default_for(state: LifecycleState) -> StateRequirements {
+        StateRequirements {
+            location: state == LifecycleState::Distributed,
+            quantity: state == LifecycleState::PlantHarvested,
+            lab_analysis: state == LifecycleState::Processed,
+            licensed_holder: state == LifecycleState::Distributed,
+        }
+    }
+}
@@ -1,13 +1,13 @@
+pub use seed_common::admin::ROLE_ADMIN;
 use soroban_sdk::{symbol_short, Symbol};
 
-pub const ROLE_ADMIN: Symbol = symbol_short!("ADMIN");
 pub const ROLE_CULTIVATOR: Symbol = symbol_short!("CULTIVAT");
 pub const ROLE_PROCESSOR: Symbol = symbol_short!("PROCESS");
 pub const ROLE_DISPENSARY: Symbol = symbol_short!("DISPENS");
+pub const ROLE_LAB: Symbol = symbol_short!("LAB");
+pub const ROLE_REGULATOR: Symbol = symbol_short!("REGULATOR");
+pub const ROLE_ATTESTER: Symbol = symbol_short!("ATTESTER");
+pub const ROLE_CUSTOMS: Symbol = symbol_short!("CUSTOMS");
 #[allow(dead_code)]
 pub const ROLE_CONSUMER: Symbol = symbol_short!("CONSUMER");
 
-pub fn get_role_key(role: Symbol) -> Symbol {
-    role
-}
-
@@ -1,47 +1,64 @@
 #![cfg(test)]
 use soroban_sdk::{
-    symbol_short, testutils::Address as _, Address, Env, String, Symbol,
+    symbol_short, testutils::{Address as _, Ledger}, Address, BytesN, Env, String, Vec,
+};
+use crate::{Error, SeedNFTContract, SeedNFTContractClient};
+use crate::lifecycle::{ConsumedPolicy, LifecycleState};
+use crate::border::{CrossingKind, CustomsClearance, ExportPermit};
+use crate::destruction::DestructionMethod;
+use crate::history::{HistoryEntry, Origin};
+use crate::metadata::{LotSpec, ProductSpec, QuantityUnit};
+use crate::requirements::StateRequirements;
+use crate::transfers::TransferRule;
+use crate::lab::{Analyte, AnalyteKind, LabSubmission, PanelVerdict, Terpene};
+use crate::compliance::{ActionLimit, ComplianceThresholds};
+use crate::recall::RecallScope;
+use crate::license::{License, LicenseStatus, LicenseType};
+use crate::purchases::PurchaseLimit;
+use crate::whitelist::{Attestation, AttestationType, WhitelistCategory};
+use seed_registry::{SeedRegistry, SeedRegistryClient};
+use seed_registry::license::LicenseType as RegistryLicenseType;
+use seed_registry::seed_bank::{SeedBank, SeedBankStatus};
+use crate::roles::{
+    ROLE_ADMIN, ROLE_ATTESTER, ROLE_CULTIVATOR, ROLE_CUSTOMS, ROLE_PROCESSOR, ROLE_DISPENSARY, ROLE_LAB, ROLE_REGULATOR,
 };
-use crate::{SeedNFTContract, SeedNFTContractClient};
-use crate::lifecycle::LifecycleState;
-use crate::roles::{ROLE_ADMIN, ROLE_CULTIVATOR, ROLE_PROCESSOR, ROLE_DISPENSARY};
 
-#[test]
-fn test_initialize() {
+fn setup() -> (Env, SeedNFTContractClient<'static>, Address) {
     let env = Env::default();
+    env.mock_all_auths();
     let admin = Address::generate(&env);
-    let name = String::from_str(&env, "Cannabis Seed NFT");
-    let symbol = String::from_str(&env, "CSNFT");
     
-    let contract_id = env.register_contract(None, SeedNFTContract);
+    let contract_id = env.register(SeedNFTContract, ());
     let client = SeedNFTContractClient::new(&env, &contract_id);
     
-    client.initialize(&admin, &name, &symbol);
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Cannabis Seed NFT"),
+        &String::from_str(&env, "CSNFT"),
+    );
+    (env, client, admin)
+}
+
+#[test]
+fn test_initialize() {
+    let (env, client, admin) = setup();
     
-    assert_eq!(client.name(), name);
-    assert_eq!(client.symbol(), symbol);
+    assert_eq!(client.name(), String::from_str(&env, "Cannabis Seed NFT"));
+    assert_eq!(client.symbol(), String::from_str(&env, "CSNFT"));
     assert!(client.has_role(&admin, &ROLE_ADMIN));
     assert!(!client.is_paused());
 }
 
 #[test]
 fn test_mint() {
-    let env = Env::default();
-    let admin = Address::generate(&env);
+    let (env, client, admin) = setup();
     let owner = Address::generate(&env);
-    let name = String::from_str(&env, "Cannabis Seed NFT");
-    let symbol = String::from_str(&env, "CSNFT");
-    
-    let contract_id = env.register_contract(None, SeedNFTContract);
-    let client = SeedNFTContractClient::new(&env, &contract_id);
-    
-    client.initialize(&admin, &name, &symbol);
     
     let token_id = 1u128;
-    client.mint(&owner, &token_id);
+    mint_plant(&env, &client, &admin, &owner, token_id);
     
-    assert_eq!(client.balance_of(&owner), 1);
-    assert_eq!(client.owner_of(&token_id), owner);
+    assert_eq!(client.balance(&owner), 1);
+    assert_eq!(client.owner_of(&(token_id as u32)), owner);
     
     let metadata = client.get_metadata(&token_id);
     assert!(metadata.is_some());
@@ -52,25 +69,20 @@ fn test_mint() {
 
 #[test]
 fn test_state_transitions() {
-    let env = Env::default();
-    let admin = Address::generate(&env);
+    let (env, client, admin) = setup();
     let cultivator = Address::generate(&env);
     let processor = Address::generate(&env);
     let dispensary = Address::generate(&env);
     let owner = Address::generate(&env);
-    let name = String::from_str(&env, "Cannabis Seed NFT");
-    let symbol = String::from_str(&env, "CSNFT");
-    
-    let contract_id = env.register_contract(None, SeedNFTContract);
-    let client = SeedNFTContractClient::new(&env, &contract_id);
-    
-    client.initialize(&admin, &name, &symbol);
     client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
     client.grant_role(&admin, &processor, &ROLE_PROCESSOR);
+    issue_license(&client, &admin, &processor, LicenseType::Processing);
     client.grant_role(&admin, &dispensary, &ROLE_DISPENSARY);
+    issue_license(&client, &admin, &dispensary, LicenseType::Retail);
     
     let token_id = 1u128;
-    client.mint(&owner, &token_id);
+    mint_plant(&env, &client, &admin, &owner, token_id);
     
     client.update_state(
         &cultivator,
@@ -86,25 +98,17 @@ fn test_state_transitions() {
     assert_eq!(metadata.state as u32, LifecycleState::Germinated as u32);
     
     let history = client.get_history(&token_id);
-    assert_eq!(history.len(), 1);
+    assert_eq!(history.len(), 2);
 }
 
 #[test]
 fn test_whitelist() {
-    let env = Env::default();
-    let admin = Address::generate(&env);
+    let (env, client, admin) = setup();
     let account = Address::generate(&env);
-    let name = String::from_str(&env, "Cannabis Seed NFT");
-    let symbol = String::from_str(&env, "CSNFT");
-    
-    let contract_id = env.register_contract(None, SeedNFTContract);
-    let client = SeedNFTContractClient::new(&env, &contract_id);
-    
-    client.initialize(&admin, &name, &symbol);
     
     assert!(!client.is_whitelisted(&account));
     
-    client.add_to_whitelist(&admin, &account);
+    client.add_to_whitelist(&admin, &account, &WhitelistCategory::Business, &None);
     assert!(client.is_whitelisted(&account));
     
     client.remove_from_whitelist(&admin, &account);
@@ -113,16 +117,8 @@ fn test_whitelist() {
 
 #[test]
 fn test_roles() {
-    let env = Env::default();
-    let admin = Address::generate(&env);
+    let (env, client, admin) = setup();
     let user = Address::generate(&env);
-    let name = String::from_str(&env, "Cannabis Seed NFT");
-    let symbol = String::from_str(&env, "CSNFT");
-    
-    let contract_id = env.register_contract(None, SeedNFTContract);
-    let client = SeedNFTContractClient::new(&env, &contract_id);
-    
-    client.initialize(&admin, &name, &symbol);
     
     assert!(!client.has_role(&user, &ROLE_CULTIVATOR));
     
@@ -135,15 +131,7 @@ fn test_roles() {
 
 #[test]
 fn test_pause() {
-    let env = Env::default();
-    let admin = Address::generate(&env);
-    let name = String::from_str(&env, "Cannabis Seed NFT");
-    let symbol = String::from_str(&env, "CSNFT");
-    
-    let contract_id = env.register_contract(None, SeedNFTContract);
-    let client = SeedNFTContractClient::new(&env, &contract_id);
-    
-    client.initialize(&admin, &name, &symbol);
+    let (_env, client, admin) = setup();
     
     assert!(!client.is_paused());
     
@@ -156,26 +144,18 @@ fn test_pause() {
 
 #[test]
 fn test_metadata_update() {
-    let env = Env::default();
-    let admin = Address::generate(&env);
+    let (env, client, admin) = setup();
     let cultivator = Address::generate(&env);
     let owner = Address::generate(&env);
-    let name = String::from_str(&env, "Cannabis Seed NFT");
-    let symbol = String::from_str(&env, "CSNFT");
-    
-    let contract_id = env.register_contract(None, SeedNFTContract);
-    let client = SeedNFTContractClient::new(&env, &contract_id);
-    
-    client.initialize(&admin, &name, &symbol);
     client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
     
     let token_id = 1u128;
-    client.mint(&owner, &token_id);
+    mint_plant(&env, &client, &admin, &owner, token_id);
     
     let location = Some(String::from_str(&env, "40.7128,-74.0060"));
     let temperature = Some(25i32);
     let humidity = Some(60u32);
-    let lab_analysis = Some(String::from_str(&env, "THC: 20%, CBD: 2%"));
     
     client.update_metadata(
         &cultivator,
@@ -183,13 +163,1588 @@ fn test_metadata_update() {
         &location,
         &temperature,
         &humidity,
-        &lab_analysis,
+        &None,
     );
     
     let metadata = client.get_metadata(&token_id).unwrap();
     assert_eq!(metadata.location, location);
     assert_eq!(metadata.temperature, temperature);
     assert_eq!(metadata.humidity, humidity);
-    assert_eq!(metadata.lab_analysis, lab_analysis);
 }
 
+#[test]
+fn test_clone_from_mother_plant() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.add_to_whitelist(&admin, &cultivator, &WhitelistCategory::Business, &None);
+    
+    let mother_id = 1u128;
+    client.mint(
+        &cultivator,
+        &mother_id,
+        &String::from_str(&env, "Mother Plant"),
+        &String::from_str(&env, "Mother plant"),
+        &String::from_str(&env, "ipfs://mother"),
+        &None,
+        &Vec::new(&env),
+    );
+    client.update_state(&cultivator, &mother_id, &(LifecycleState::Germinated as u32), &None, &None, &None, &None);
+    client.update_state(&cultivator, &mother_id, &(LifecycleState::PlantVegetative as u32), &None, &None, &None, &None);
+    
+    assert!(client.try_clone_from(&cultivator, &mother_id, &1, &outsider).is_err());
+    
+    let clones = client.clone_from(&cultivator, &mother_id, &3, &cultivator);
+    assert_eq!(clones.len(), 3);
+    assert_eq!(client.get_children(&mother_id), clones);
+    assert_eq!(client.get_parent(&mother_id), None);
+    
+    let clone_id = clones.get(0).unwrap();
+    assert_eq!(client.get_parent(&clone_id), Some(mother_id));
+    assert_eq!(client.owner_of(&(clone_id as u32)), cultivator);
+    
+    let metadata = client.get_metadata(&clone_id).unwrap();
+    assert_eq!(metadata.state, LifecycleState::PlantVegetative);
+    assert_eq!(metadata.parent, Some(mother_id));
+    
+    let history = client.get_history(&clone_id);
+    assert_eq!(history.len(), 2);
+    match history.get(0).unwrap() {
+        HistoryEntry::Genesis(genesis) => {
+            assert_eq!(genesis.origin, Origin::Cloned);
+            assert_eq!(genesis.sources, Vec::from_array(&env, [mother_id]));
+        }
+        _ => panic!("Expected genesis entry"),
+    }
+}
+
+#[test]
+#[should_panic(expected = "Caller is not the token owner")]
+fn test_clone_from_requires_owner() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let owner = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.add_to_whitelist(&admin, &cultivator, &WhitelistCategory::Business, &None);
+    
+    mint_plant(&env, &client, &admin, &owner, 1);
+    client.update_state(&cultivator, &1, &(LifecycleState::Germinated as u32), &None, &None, &None, &None);
+    client.update_state(&cultivator, &1, &(LifecycleState::PlantVegetative as u32), &None, &None, &None, &None);
+    client.clone_from(&cultivator, &1, &1, &cultivator);
+}
+
+#[test]
+fn test_split_harvested_plant() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.add_to_whitelist(&admin, &cultivator, &WhitelistCategory::Business, &None);
+    
+    let token_id = 1u128;
+    client.mint(
+        &cultivator,
+        &token_id,
+        &String::from_str(&env, "Plant"),
+        &String::from_str(&env, "Harvested plant"),
+        &String::from_str(&env, "ipfs://plant"),
+        &None,
+        &Vec::new(&env),
+    );
+    for state in [
+        LifecycleState::Germinated,
+        LifecycleState::PlantVegetative,
+        LifecycleState::PlantFlowering,
+    ] {
+        client.update_state(&cultivator, &token_id, &(state as u32), &None, &None, &None, &None);
+    }
+    client.set_quantity(&cultivator, &token_id, &600, &QuantityUnit::Grams);
+    client.update_state(&cultivator, &token_id, &(LifecycleState::PlantHarvested as u32), &None, &None, &None, &None);
+    
+    let lots = Vec::from_array(
+        &env,
+        [
+            LotSpec { product_type: String::from_str(&env, "flower"), quantity: 400 },
+            LotSpec { product_type: String::from_str(&env, "trim"), quantity: 150 },
+        ],
+    );
+    client.grant_role(&admin, &other, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &other, LicenseType::Cultivation);
+    assert!(client.try_split(&other, &token_id, &lots).is_err());
+    
+    let lot_ids = client.split(&cultivator, &token_id, &lots);
+    assert_eq!(lot_ids.len(), 2);
+    assert_eq!(client.get_children(&token_id), lot_ids);
+    
+    let parent = client.get_metadata(&token_id).unwrap();
+    assert_eq!(parent.state, LifecycleState::Split);
+    assert_eq!(parent.quantity, Some(0));
+    
+    let balance = client.get_mass_balance(&token_id).unwrap();
+    assert_eq!(balance.initial, 600);
+    assert_eq!(balance.split, 550);
+    assert_eq!(balance.loss, 50);
+    assert_eq!(balance.remaining, 0);
+    
+    let flower = client.get_metadata(&lot_ids.get(0).unwrap()).unwrap();
+    assert_eq!(flower.state, LifecycleState::PlantHarvested);
+    assert_eq!(flower.parent, Some(token_id));
+    assert_eq!(flower.product_type, Some(String::from_str(&env, "flower")));
+    assert_eq!(flower.quantity, Some(400));
+    assert_eq!(client.get_mass_balance(&lot_ids.get(0).unwrap()).unwrap().unit, QuantityUnit::Grams);
+    assert_eq!(client.owner_of(&(lot_ids.get(1).unwrap() as u32)), cultivator);
+    match client.get_history(&lot_ids.get(0).unwrap()).get(0).unwrap() {
+        HistoryEntry::Genesis(genesis) => {
+            assert_eq!(genesis.origin, Origin::Split);
+            assert_eq!(genesis.state, LifecycleState::PlantHarvested);
+            assert_eq!(genesis.sources, Vec::from_array(&env, [token_id]));
+        }
+        _ => panic!("Expected genesis entry"),
+    }
+}
+
+fn issue_license(
+    client: &SeedNFTContractClient,
+    admin: &Address,
+    account: &Address,
+    license_type: LicenseType,
+) {
+    let license = License {
+        number: String::from_str(&client.env, "LIC-0001"),
+        license_type,
+        jurisdiction: String::from_str(&client.env, "US-CA"),
+        issued_at: 0,
+        expires_at: u64::MAX,
+        status: LicenseStatus::Active,
+    };
+    client.issue_license(admin, account, &license);
+}
+
+fn attest_consumer(client: &SeedNFTContractClient, admin: &Address, account: &Address) {
+    client.add_to_whitelist(admin, account, &WhitelistCategory::Consumer, &None);
+    let attester = Address::generate(&client.env);
+    client.grant_role(admin, &attester, &ROLE_ATTESTER);
+    let attestation = Attestation {
+        attester: attester.clone(),
+        attestation_type: AttestationType::Age21Plus,
+        expires_at: u64::MAX,
+        evidence_hash: BytesN::from_array(&client.env, &[1u8; 32]),
+    };
+    client.attest(&attester, account, &attestation);
+}
+
+fn mint_plant(env: &Env, client: &SeedNFTContractClient, admin: &Address, owner: &Address, token_id: u128) {
+    if !client.is_whitelisted(owner) {
+        client.add_to_whitelist(admin, owner, &WhitelistCategory::Business, &None);
+    }
+    client.mint(
+        owner,
+        &token_id,
+        &String::from_str(env, "Plant"),
+        &String::from_str(env, "Cannabis plant"),
+        &String::from_str(env, "ipfs://plant"),
+        &None,
+        &Vec::new(env),
+    );
+}
+
+fn harvest(client: &SeedNFTContractClient, cultivator: &Address, token_id: u128, quantity: u64) {
+    for state in [
+        LifecycleState::Germinated,
+        LifecycleState::PlantVegetative,
+        LifecycleState::PlantFlowering,
+    ] {
+        client.update_state(cultivator, &token_id, &(state as u32), &None, &None, &None, &None);
+    }
+    let owner = client.owner_of(&(token_id as u32));
+    if owner != *cultivator {
+        client.approve(&owner, cultivator, &(token_id as u32), &(client.env.ledger().sequence() + 1000));
+    }
+    client.set_quantity(cultivator, &token_id, &quantity, &QuantityUnit::Grams);
+    client.update_state(
+        cultivator,
+        &token_id,
+        &(LifecycleState::PlantHarvested as u32),
+        &None,
+        &None,
+        &None,
+        &None,
+    );
+}
+
+fn lab_result(env: &Env, passing: bool) -> LabSubmission {
+    let pesticide = Analyte {
+        kind: AnalyteKind::Pesticide,
+        name: String::from_str(env, "myclobutanil"),
+        value: if passing { 0 } else { 300 },
+    };
+    LabSubmission {
+        sampled_at: env.ledger().timestamp(),
+        thc_bps: 2000,
+        thca_bps: 0,
+        cbd_bps: 200,
+        terpenes: Vec::from_array(
+            env,
+            [Terpene { name: String::from_str(env, "myrcene"), bps: 80 }],
+        ),
+        analytes: Vec::from_array(env, [pesticide]),
+        moisture_bps: 1100,
+        certificate_hash: BytesN::from_array(env, &[7u8; 32]),
+    }
+}
+
+fn lab_test_and_process(client: &SeedNFTContractClient, admin: &Address, processor: &Address, token_id: u128) {
+    let lab = Address::generate(&client.env);
+    client.grant_role(admin, &lab, &ROLE_LAB);
+    issue_license(client, admin, &lab, LicenseType::Lab);
+    client.set_lab_accreditation(admin, &lab, &u64::MAX);
+    client.submit_lab_result(&lab, &token_id, &lab_result(&client.env, true));
+    client.update_state(processor, &token_id, &(LifecycleState::Processed as u32), &None, &None, &None, &None);
+}
+
+fn process_and_distribute(
+    client: &SeedNFTContractClient,
+    admin: &Address,
+    processor: &Address,
+    dispensary: &Address,
+    owner: &Address,
+    token_id: u128,
+) {
+    lab_test_and_process(client, admin, processor, token_id);
+    client.add_to_whitelist(admin, owner, &WhitelistCategory::Business, &None);
+    issue_license(client, admin, owner, LicenseType::Retail);
+    client.update_state(
+        dispensary,
+        &token_id,
+        &(LifecycleState::Distributed as u32),
+        &Some(String::from_str(&client.env, "Dispensary 1")),
+        &None,
+        &None,
+        &None,
+    );
+}
+
+#[test]
+fn test_merge_lots_into_product() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let processor = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.grant_role(&admin, &processor, &ROLE_PROCESSOR);
+    issue_license(&client, &admin, &processor, LicenseType::Processing);
+    client.add_to_whitelist(&admin, &processor, &WhitelistCategory::Business, &None);
+    
+    mint_plant(&env, &client, &admin, &owner, 1);
+    mint_plant(&env, &client, &admin, &owner, 2);
+    harvest(&client, &cultivator, 1, 300);
+    harvest(&client, &cultivator, 2, 200);
+    
+    let inputs = Vec::from_array(&env, [1u128, 2u128]);
+    let spec = ProductSpec {
+        name: String::from_str(&env, "Blend Pre-roll"),
+        description: String::from_str(&env, "Pre-roll batch"),
+        image: String::from_str(&env, "ipfs://preroll"),
+        product_type: String::from_str(&env, "pre-roll"),
+        quantity: 450,
+    };
+    client.approve(&owner, &processor, &1, &1_000);
+    assert!(client.try_merge(&processor, &inputs, &spec, &processor).is_err());
+    client.approve(&owner, &processor, &2, &1_000);
+    assert!(client.try_merge(&processor, &inputs, &spec, &outsider).is_err());
+    let product_id = client.merge(&processor, &inputs, &spec, &processor);
+    
+    assert_eq!(client.get_inputs(&product_id), inputs);
+    assert_eq!(client.get_children(&1), Vec::from_array(&env, [product_id]));
+    assert_eq!(client.get_metadata(&1).unwrap().state, LifecycleState::Merged);
+    assert_eq!(client.get_metadata(&2).unwrap().state, LifecycleState::Merged);
+    
+    let product = client.get_metadata(&product_id).unwrap();
+    assert_eq!(product.state, LifecycleState::PlantHarvested);
+    assert_eq!(product.processor, None);
+    assert_eq!(product.quantity, Some(450));
+    assert_eq!(client.get_mass_balance(&1).unwrap().merged, 300);
+    assert_eq!(client.get_mass_balance(&1).unwrap().remaining, 0);
+    assert_eq!(client.owner_of(&(product_id as u32)), processor);
+    
+    let processed = LifecycleState::Processed as u32;
+    let result = client.try_update_state(&processor, &product_id, &processed, &None, &None, &None, &None);
+    assert_eq!(result, Err(Ok(Error::MissingLabAnalysis.into())));
+    lab_test_and_process(&client, &admin, &processor, product_id);
+    assert_eq!(client.get_metadata(&product_id).unwrap().processor, Some(processor));
+}
+
+#[test]
+fn test_set_quantity_requires_owner_and_open_token() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let owner = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    
+    mint_plant(&env, &client, &admin, &owner, 1);
+    assert!(client.try_set_quantity(&cultivator, &1, &100, &QuantityUnit::Grams).is_err());
+    client.approve(&owner, &cultivator, &1, &(env.ledger().sequence() + 1000));
+    client.set_quantity(&cultivator, &1, &100, &QuantityUnit::Grams);
+    
+    client.freeze_token(&admin, &1, &String::from_str(&env, "Inspection hold"));
+    assert!(client.try_set_quantity(&cultivator, &1, &0, &QuantityUnit::Grams).is_err());
+    client.unfreeze_token(&admin, &1);
+    
+    mint_plant(&env, &client, &admin, &cultivator, 2);
+    harvest(&client, &cultivator, 2, 100);
+    let lots = Vec::from_array(&env, [LotSpec { product_type: String::from_str(&env, "flower"), quantity: 100 }]);
+    client.split(&cultivator, &2, &lots);
+    assert!(client.try_set_quantity(&cultivator, &2, &0, &QuantityUnit::Grams).is_err());
+    assert_eq!(client.get_mass_balance(&1).unwrap().remaining, 100);
+}
+
+#[test]
+fn test_merge_reconciles_mass_balance() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let processor = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.grant_role(&admin, &processor, &ROLE_PROCESSOR);
+    issue_license(&client, &admin, &processor, LicenseType::Processing);
+    client.add_to_whitelist(&admin, &processor, &WhitelistCategory::Business, &None);
+    
+    mint_plant(&env, &client, &admin, &processor, 1);
+    mint_plant(&env, &client, &admin, &processor, 2);
+    harvest(&client, &cultivator, 1, 300);
+    harvest(&client, &cultivator, 2, 200);
+    
+    assert!(client.try_set_quantity(&processor, &1, &310, &QuantityUnit::Grams).is_err());
+    assert!(client.try_set_quantity(&processor, &1, &280, &QuantityUnit::Milligrams).is_err());
+    client.set_quantity(&processor, &1, &280, &QuantityUnit::Grams);
+    let dried = client.get_mass_balance(&1).unwrap();
+    assert_eq!(dried.loss, 20);
+    assert_eq!(dried.remaining, 280);
+    assert_eq!(client.get_metadata(&1).unwrap().quantity, Some(280));
+    
+    let spec = ProductSpec {
+        name: String::from_str(&env, "Blend"),
+        description: String::from_str(&env, "Blended flower"),
+        image: String::from_str(&env, "ipfs://blend"),
+        product_type: String::from_str(&env, "flower"),
+        quantity: 450,
+    };
+    let product_id = client.merge(&processor, &Vec::from_array(&env, [1u128, 2u128]), &spec, &processor);
+    
+    let first = client.get_mass_balance(&1).unwrap();
+    let second = client.get_mass_balance(&2).unwrap();
+    let product = client.get_mass_balance(&product_id).unwrap();
+    assert_eq!(first.initial, first.merged + first.loss);
+    assert_eq!(second.initial, second.merged);
+    assert_eq!(product.initial, first.merged + second.merged);
+    assert_eq!(product.loss, 30);
+    assert_eq!(product.remaining, product.initial - product.loss);
+    assert_eq!(client.get_metadata(&product_id).unwrap().quantity, Some(product.remaining));
+}
+
+#[test]
+#[should_panic(expected = "Outputs exceed available quantity")]
+fn test_split_rejects_outputs_exceeding_input() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    
+    mint_plant(&env, &client, &admin, &cultivator, 1);
+    harvest(&client, &cultivator, 1, 100);
+    
+    let lots = Vec::from_array(
+        &env,
+        [
+            LotSpec { product_type: String::from_str(&env, "flower"), quantity: 80 },
+            LotSpec { product_type: String::from_str(&env, "trim"), quantity: 30 },
+        ],
+    );
+    client.split(&cultivator, &1, &lots);
+}
+
+#[test]
+fn test_sell_partial_closes_lot() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let processor = Address::generate(&env);
+    let dispensary = Address::generate(&env);
+    let consumer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.grant_role(&admin, &processor, &ROLE_PROCESSOR);
+    issue_license(&client, &admin, &processor, LicenseType::Processing);
+    client.grant_role(&admin, &dispensary, &ROLE_DISPENSARY);
+    issue_license(&client, &admin, &dispensary, LicenseType::Retail);
+    
+    mint_plant(&env, &client, &admin, &dispensary, 1);
+    harvest(&client, &cultivator, 1, 1000);
+    process_and_distribute(&client, &admin, &processor, &dispensary, &dispensary, 1);
+    
+    attest_consumer(&client, &admin, &consumer);
+    client.grant_role(&admin, &stranger, &ROLE_DISPENSARY);
+    issue_license(&client, &admin, &stranger, LicenseType::Retail);
+    assert!(client.try_sell_partial(&stranger, &1, &400, &consumer, &true).is_err());
+    assert!(client.try_sell_partial(&dispensary, &1, &400, &stranger, &true).is_err());
+    
+    let unit_id = client.sell_partial(&dispensary, &1, &400, &consumer, &true).unwrap();
+    assert_eq!(client.owner_of(&(unit_id as u32)), consumer);
+    assert_eq!(client.get_parent(&unit_id), Some(1));
+    assert_eq!(client.get_metadata(&1).unwrap().quantity, Some(600));
+    assert_eq!(client.get_metadata(&1).unwrap().state, LifecycleState::Distributed);
+    match client.get_history(&unit_id).get(0).unwrap() {
+        HistoryEntry::Genesis(genesis) => assert_eq!(genesis.origin, Origin::Sold),
+        _ => panic!("Expected genesis entry"),
+    }
+    
+    client.set_consumed_policy(&admin, &ConsumedPolicy::Burn);
+    assert_eq!(client.sell_partial(&dispensary, &1, &100, &consumer, &true), None);
+    assert_eq!(client.balance(&consumer), 1);
+    
+    assert_eq!(client.sell_partial(&dispensary, &1, &500, &consumer, &false), None);
+    let lot = client.get_metadata(&1).unwrap();
+    assert_eq!(lot.state, LifecycleState::Consumed);
+    assert_eq!(lot.quantity, Some(0));
+    
+    let regulator = Address::generate(&env);
+    client.grant_role(&admin, &regulator, &ROLE_REGULATOR);
+    assert!(client.try_get_sales(&stranger, &1).is_err());
+    assert!(!client.get_history(&1).iter().any(|entry| matches!(entry, HistoryEntry::Sale(_))));
+    assert_eq!(client.get_metadata(&unit_id).unwrap().consumer, None);
+    let sales = client.get_sales(&regulator, &1);
+    assert_eq!(sales.len(), 3);
+    assert_eq!(sales.get(0).unwrap().consumer, consumer);
+    assert_eq!(sales.get(0).unwrap().unit_token, Some(unit_id));
+    assert_eq!(client.get_mass_balance(&1).unwrap().sold, 1000);
+}
+
+#[test]
+fn test_mint_paths_check_recipient() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let processor = Address::generate(&env);
+    let consumer = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.grant_role(&admin, &processor, &ROLE_PROCESSOR);
+    issue_license(&client, &admin, &processor, LicenseType::Processing);
+    client.add_to_whitelist(&admin, &consumer, &WhitelistCategory::Consumer, &None);
+    
+    let name = String::from_str(&env, "Plant");
+    let image = String::from_str(&env, "ipfs://plant");
+    assert!(client.try_mint(&outsider, &1, &name, &name, &image, &None, &Vec::new(&env)).is_err());
+    assert!(client.try_mint(&consumer, &1, &name, &name, &image, &None, &Vec::new(&env)).is_err());
+    
+    mint_plant(&env, &client, &admin, &cultivator, 1);
+    client.update_state(&cultivator, &1, &(LifecycleState::Germinated as u32), &None, &None, &None, &None);
+    client.update_state(&cultivator, &1, &(LifecycleState::PlantVegetative as u32), &None, &None, &None, &None);
+    assert!(client.try_clone_from(&cultivator, &1, &1, &consumer).is_err());
+    
+    mint_plant(&env, &client, &admin, &processor, 2);
+    mint_plant(&env, &client, &admin, &processor, 3);
+    harvest(&client, &cultivator, 2, 200);
+    harvest(&client, &cultivator, 3, 100);
+    let spec = ProductSpec {
+        name: String::from_str(&env, "Blend"),
+        description: String::from_str(&env, "Blend"),
+        image: String::from_str(&env, "ipfs://blend"),
+        product_type: String::from_str(&env, "flower"),
+        quantity: 300,
+    };
+    let inputs = Vec::from_array(&env, [2u128, 3u128]);
+    assert!(client.try_merge(&processor, &inputs, &spec, &outsider).is_err());
+    assert!(client.try_merge(&processor, &inputs, &spec, &consumer).is_err());
+    assert_eq!(client.get_metadata(&2).unwrap().state, LifecycleState::PlantHarvested);
+}
+
+#[test]
+fn test_update_state_batch() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let owner = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    
+    mint_plant(&env, &client, &admin, &owner, 1);
+    mint_plant(&env, &client, &admin, &owner, 2);
+    
+    let token_ids = Vec::from_array(&env, [1u128, 2u128, 99u128]);
+    let outcomes = client.update_state_batch(
+        &cultivator,
+        &token_ids,
+        &(LifecycleState::Germinated as u32),
+        &Some(String::from_str(&env, "Grow room A")),
+        &None,
+        &None,
+        &None,
+    );
+    
+    assert_eq!(outcomes.len(), 3);
+    assert!(outcomes.get(0).unwrap().success);
+    assert!(outcomes.get(1).unwrap().success);
+    assert!(!outcomes.get(2).unwrap().success);
+    assert_eq!(outcomes.get(2).unwrap().error, Some(Error::TokenNotFound as u32));
+    assert_eq!(client.get_metadata(&2).unwrap().state, LifecycleState::Germinated);
+    
+    client.set_batch_limit(&admin, &2);
+    assert_eq!(client.get_batch_limit(), 2);
+    let result = client.try_update_state_batch(
+        &cultivator,
+        &token_ids,
+        &(LifecycleState::PlantVegetative as u32),
+        &None,
+        &None,
+        &None,
+        &None,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+#[should_panic(expected = "Invalid state transition")]
+fn test_update_state_keeps_string_panics() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let owner = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    
+    mint_plant(&env, &client, &admin, &owner, 1);
+    client.update_state(&cultivator, &1, &(LifecycleState::PlantHarvested as u32), &None, &None, &None, &None);
+}
+
+#[test]
+fn test_state_requirements() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let processor = Address::generate(&env);
+    let owner = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.grant_role(&admin, &processor, &ROLE_PROCESSOR);
+    issue_license(&client, &admin, &processor, LicenseType::Processing);
+    
+    mint_plant(&env, &client, &admin, &owner, 1);
+    for state in [
+        LifecycleState::Germinated,
+        LifecycleState::PlantVegetative,
+        LifecycleState::PlantFlowering,
+    ] {
+        client.update_state(&cultivator, &1, &(state as u32), &None, &None, &None, &None);
+    }
+    
+    let harvested = LifecycleState::PlantHarvested as u32;
+    let result = client.try_update_state(&cultivator, &1, &harvested, &None, &None, &None, &None);
+    assert_eq!(result, Err(Ok(Error::MissingQuantity.into())));
+    
+    client.approve(&owner, &cultivator, &1, &(env.ledger().sequence() + 1000));
+    client.set_quantity(&cultivator, &1, &500, &QuantityUnit::Grams);
+    client.update_state(&cultivator, &1, &harvested, &None, &None, &None, &None);
+    
+    let processed = LifecycleState::Processed as u32;
+    let result = client.try_update_state(&processor, &1, &processed, &None, &None, &None, &None);
+    assert_eq!(result, Err(Ok(Error::MissingLabAnalysis.into())));
+    
+    let relaxed = StateRequirements {
+        location: false,
+        quantity: false,
+        lab_analysis: false,
+        licensed_holder: false,
+    };
+    client.set_state_requirements(&admin, &processed, &relaxed);
+    assert_eq!(client.get_state_requirements(&processed), relaxed);
+    client.update_state(&processor, &1, &processed, &None, &None, &None, &None);
+    assert_eq!(client.get_metadata(&1).unwrap().state, LifecycleState::Processed);
+}
+
+#[test]
+fn test_expired_products() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let processor = Address::generate(&env);
+    let dispensary = Address::generate(&env);
+    let owner = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.grant_role(&admin, &processor, &ROLE_PROCESSOR);
+    issue_license(&client, &admin, &processor, LicenseType::Processing);
+    client.grant_role(&admin, &dispensary, &ROLE_DISPENSARY);
+    issue_license(&client, &admin, &dispensary, LicenseType::Retail);
+    client.set_shelf_life(&admin, &1000);
+    
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+    for token_id in [1, 2] {
+        mint_plant(&env, &client, &admin, &owner, token_id);
+        harvest(&client, &cultivator, token_id, 100);
+        process_and_distribute(&client, &admin, &processor, &dispensary, &owner, token_id);
+    }
+    
+    assert_eq!(client.get_metadata(&1).unwrap().expires_at, Some(11_000));
+    assert_eq!(client.get_expiring_count(), 2);
+    assert_eq!(client.get_expired_tokens(&0, &10).len(), 0);
+    
+    env.ledger().with_mut(|li| li.timestamp = 11_000);
+    assert_eq!(client.get_expired_tokens(&0, &10), Vec::from_array(&env, [1u128, 2]));
+    assert_eq!(client.get_expired_tokens(&1, &10), Vec::from_array(&env, [2u128]));
+    
+    let consumed = LifecycleState::Consumed as u32;
+    let result = client.try_update_state(&dispensary, &1, &consumed, &None, &None, &None, &None);
+    assert_eq!(result, Err(Ok(Error::ProductExpired.into())));
+    
+    let witnesses = Vec::from_array(&env, [dispensary.clone()]);
+    client.record_destruction(&owner, &1, &100, &DestructionMethod::Incineration, &witnesses);
+    assert_eq!(client.get_expiring_count(), 1);
+    assert_eq!(client.get_expired_tokens(&0, &10), Vec::from_array(&env, [2u128]));
+}
+
+#[test]
+fn test_consumed_policy() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let processor = Address::generate(&env);
+    let dispensary = Address::generate(&env);
+    let owner = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.grant_role(&admin, &processor, &ROLE_PROCESSOR);
+    issue_license(&client, &admin, &processor, LicenseType::Processing);
+    client.grant_role(&admin, &dispensary, &ROLE_DISPENSARY);
+    issue_license(&client, &admin, &dispensary, LicenseType::Retail);
+    assert_eq!(client.get_consumed_policy(), ConsumedPolicy::Lock);
+    
+    let consumed = LifecycleState::Consumed as u32;
+    for token_id in [1u128, 2u128] {
+        mint_plant(&env, &client, &admin, &owner, token_id);
+        harvest(&client, &cultivator, token_id, 100);
+        process_and_distribute(&client, &admin, &processor, &dispensary, &owner, token_id);
+    }
+    
+    attest_consumer(&client, &admin, &owner);
+    client.update_state(&dispensary, &1, &consumed, &None, &None, &None, &None);
+    assert_eq!(client.owner_of(&1), owner);
+    assert!(client.try_transfer(&owner, &admin, &1).is_err());
+    
+    client.set_consumed_policy(&admin, &ConsumedPolicy::Burn);
+    client.update_state(&dispensary, &2, &consumed, &None, &None, &None, &None);
+    assert_eq!(client.balance(&owner), 1);
+    assert_eq!(client.get_metadata(&2).unwrap().consumer, None);
+    let regulator = Address::generate(&env);
+    client.grant_role(&admin, &regulator, &ROLE_REGULATOR);
+    assert_eq!(client.get_compliance_record(&regulator, &2).consumer, Some(owner.clone()));
+    assert_eq!(client.get_history(&2).len(), 9);
+    let custody = client.get_custody(&2);
+    assert_eq!(custody.get(0).unwrap().from, None);
+    assert_eq!(custody.last().unwrap().from, Some(owner.clone()));
+    assert_eq!(custody.last().unwrap().to, None);
+}
+
+#[test]
+fn test_structured_lab_results() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let processor = Address::generate(&env);
+    let owner = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.grant_role(&admin, &processor, &ROLE_PROCESSOR);
+    issue_license(&client, &admin, &processor, LicenseType::Processing);
+    
+    let lab = Address::generate(&env);
+    client.grant_role(&admin, &lab, &ROLE_LAB);
+    issue_license(&client, &admin, &lab, LicenseType::Lab);
+    client.set_lab_accreditation(&admin, &lab, &u64::MAX);
+    
+    mint_plant(&env, &client, &admin, &owner, 1);
+    harvest(&client, &cultivator, 1, 100);
+    
+    let failing = lab_result(&env, false);
+    client.submit_lab_result(&lab, &1, &failing);
+    
+    let processed = LifecycleState::Processed as u32;
+    let result = client.try_update_state(&processor, &1, &processed, &None, &None, &None, &None);
+    assert_eq!(result, Err(Ok(Error::TokenFrozen.into())));
+    
+    client.unfreeze_token(&admin, &1);
+    let result = client.try_update_state(&processor, &1, &processed, &None, &None, &None, &None);
+    assert_eq!(result, Err(Ok(Error::FailedLabAnalysis.into())));
+    
+    let passing = lab_result(&env, true);
+    client.submit_lab_result(&lab, &1, &passing);
+    client.update_state(&processor, &1, &processed, &None, &None, &None, &None);
+    
+    let results = client.get_lab_results(&1);
+    assert_eq!(results.len(), 2);
+    assert!(!results.get(0).unwrap().passed);
+    assert!(results.get(1).unwrap().passed);
+    assert_eq!(results.get(1).unwrap().thc_bps, 2000);
+    assert_eq!(results.get(1).unwrap().lab, lab);
+}
+
+#[test]
+fn test_lab_action_limits_and_total_thc() {
+    let (env, client, admin) = setup();
+    let lab = Address::generate(&env);
+    let owner = Address::generate(&env);
+    client.grant_role(&admin, &lab, &ROLE_LAB);
+    issue_license(&client, &admin, &lab, LicenseType::Lab);
+    client.set_lab_accreditation(&admin, &lab, &u64::MAX);
+    mint_plant(&env, &client, &admin, &owner, 1);
+    mint_plant(&env, &client, &admin, &owner, 2);
+    mint_plant(&env, &client, &admin, &owner, 3);
+    
+    let thresholds = ComplianceThresholds {
+        max_total_thc_bps: Some(2000),
+        max_moisture_bps: Some(1500),
+        action_limits: Vec::from_array(
+            &env,
+            [
+                ActionLimit {
+                    kind: AnalyteKind::Pesticide,
+                    analyte: Some(String::from_str(&env, "myclobutanil")),
+                    max: 500,
+                },
+                ActionLimit { kind: AnalyteKind::Pesticide, analyte: None, max: 100 },
+            ],
+        ),
+    };
+    client.set_compliance_thresholds(&admin, &thresholds);
+    
+    let mut submission = lab_result(&env, false);
+    submission.thc_bps = 100;
+    submission.thca_bps = 2000;
+    client.submit_lab_result(&lab, &1, &submission);
+    let result = client.get_lab_results(&1).get(0).unwrap();
+    assert_eq!(result.total_thc_bps, 1854);
+    assert!(result.passed);
+    assert!(result.potency_passed && result.moisture_passed);
+    assert_eq!(result.panels.len(), AnalyteKind::ALL.len() as u32);
+    assert!(result.panels.iter().all(|panel| panel.passed));
+    
+    submission.thca_bps = 2200;
+    client.submit_lab_result(&lab, &2, &submission);
+    let result = client.get_lab_results(&2).get(0).unwrap();
+    assert!(!result.passed);
+    assert!(!result.potency_passed);
+    assert!(result.panels.iter().all(|panel| panel.passed));
+    
+    submission.thca_bps = 0;
+    submission.analytes.push_back(Analyte {
+        kind: AnalyteKind::Pesticide,
+        name: String::from_str(&env, "bifenazate"),
+        value: 150,
+    });
+    submission.moisture_bps = 1600;
+    client.submit_lab_result(&lab, &3, &submission);
+    let result = client.get_lab_results(&3).get(0).unwrap();
+    assert!(!result.passed);
+    assert!(result.potency_passed);
+    assert!(!result.moisture_passed);
+    assert!(result.panels.contains(PanelVerdict { kind: AnalyteKind::Pesticide, passed: false }));
+    assert!(result.panels.contains(PanelVerdict { kind: AnalyteKind::HeavyMetal, passed: true }));
+    assert!(client.is_frozen(&3));
+}
+
+#[test]
+fn test_lab_accreditation() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let lab = Address::generate(&env);
+    let owner = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.grant_role(&admin, &lab, &ROLE_LAB);
+    issue_license(&client, &admin, &lab, LicenseType::Lab);
+    mint_plant(&env, &client, &admin, &owner, 1);
+    
+    let result = lab_result(&env, true);
+    assert!(client.try_submit_lab_result(&cultivator, &1, &result).is_err());
+    assert!(client.try_submit_lab_result(&lab, &1, &result).is_err());
+    
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    client.set_lab_accreditation(&admin, &lab, &2_000);
+    assert_eq!(client.get_lab_accreditation(&lab), Some(2_000));
+    client.submit_lab_result(&lab, &1, &result);
+    assert_eq!(client.get_lab_results(&1).len(), 1);
+    
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    assert!(client.try_submit_lab_result(&lab, &1, &result).is_err());
+}
+
+#[test]
+fn test_thresholds_quarantine_failing_lots() {
+    let (env, client, admin) = setup();
+    let lab = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.grant_role(&admin, &lab, &ROLE_LAB);
+    issue_license(&client, &admin, &lab, LicenseType::Lab);
+    client.set_lab_accreditation(&admin, &lab, &u64::MAX);
+    client.add_to_whitelist(&admin, &recipient, &WhitelistCategory::Business, &None);
+    mint_plant(&env, &client, &admin, &owner, 1);
+    
+    let hemp = ComplianceThresholds {
+        max_total_thc_bps: Some(30),
+        max_moisture_bps: None,
+        action_limits: Vec::new(&env),
+    };
+    client.set_compliance_thresholds(&admin, &hemp);
+    assert_eq!(client.get_compliance_thresholds(), hemp);
+    
+    client.submit_lab_result(&lab, &1, &lab_result(&env, true));
+    assert!(!client.get_lab_results(&1).get(0).unwrap().passed);
+    assert!(client.is_frozen(&1));
+    assert_eq!(client.get_freeze(&1).unwrap().frozen_by, lab);
+    assert_eq!(
+        client.try_transfer(&owner, &recipient, &1),
+        Err(Ok(Error::TokenFrozen.into()))
+    );
+    
+    client.unfreeze_token(&admin, &1);
+    assert!(!client.is_frozen(&1));
+    client.transfer(&owner, &recipient, &1);
+    assert_eq!(client.owner_of(&1), recipient);
+}
+
+#[test]
+fn test_recall_covers_descendants() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let processor = Address::generate(&env);
+    let owner = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.grant_role(&admin, &processor, &ROLE_PROCESSOR);
+    issue_license(&client, &admin, &processor, LicenseType::Processing);
+    
+    mint_plant(&env, &client, &admin, &cultivator, 1);
+    mint_plant(&env, &client, &admin, &owner, 2);
+    harvest(&client, &cultivator, 1, 100);
+    harvest(&client, &cultivator, 2, 100);
+    
+    let lots = Vec::from_array(
+        &env,
+        [
+            LotSpec { product_type: String::from_str(&env, "flower"), quantity: 60 },
+            LotSpec { product_type: String::from_str(&env, "trim"), quantity: 40 },
+        ],
+    );
+    let lot_ids = client.split(&cultivator, &1, &lots);
+    let flower_id = lot_ids.get(0).unwrap();
+    
+    let spec = ProductSpec {
+        name: String::from_str(&env, "Blend"),
+        description: String::from_str(&env, "Blended flower"),
+        image: String::from_str(&env, "ipfs://blend"),
+        product_type: String::from_str(&env, "flower"),
+        quantity: 160,
+    };
+    client.add_to_whitelist(&admin, &owner, &WhitelistCategory::Business, &None);
+    client.approve(&cultivator, &processor, &(flower_id as u32), &1_000);
+    client.approve_for_all(&owner, &processor, &1_000);
+    let product_id = client.merge(&processor, &Vec::from_array(&env, [flower_id, 2u128]), &spec, &owner);
+    lab_test_and_process(&client, &admin, &processor, product_id);
+    
+    let reason = String::from_str(&env, "Contaminated source plant");
+    let recall_id = client.recall(&admin, &RecallScope::Token(1), &reason);
+    
+    let recall = client.get_recall(&recall_id).unwrap();
+    assert_eq!(recall.reason, reason);
+    assert_eq!(recall.affected.len(), 4);
+    assert!(recall.affected.contains(product_id));
+    assert!(client.is_frozen(&1));
+    assert!(client.is_frozen(&lot_ids.get(1).unwrap()));
+    assert!(client.is_frozen(&product_id));
+    assert!(!client.is_frozen(&2));
+    
+    let by_processor = client.recall(&admin, &RecallScope::Processor(processor.clone()), &reason);
+    assert_eq!(client.get_recall(&by_processor).unwrap().affected, Vec::from_array(&env, [product_id]));
+    assert_eq!(client.get_processor_token_count(&processor), 1);
+    assert_eq!(client.get_processor_tokens(&processor, &1).len(), 0);
+}
+
+fn setup_registry(env: &Env, client: &SeedNFTContractClient, admin: &Address) -> SeedRegistryClient<'static> {
+    let registry_id = env.register(SeedRegistry, ());
+    let registry = SeedRegistryClient::new(env, &registry_id);
+    registry.initialize(admin, &client.address);
+    registry.grant_role(admin, &registry_id, &ROLE_CULTIVATOR);
+    issue_license(client, admin, &registry_id, LicenseType::Cultivation);
+    registry.approve_seed_bank(
+        admin,
+        &SeedBank {
+            id: String::from_str(env, "Bank-001"),
+            name: String::from_str(env, "Andes Genetics"),
+            country: String::from_str(env, "Colombia"),
+            certifications: Vec::new(env),
+            status: SeedBankStatus::Active,
+            approved_at: 0,
+        },
+    );
+    client.set_registry(admin, &registry_id);
+    registry
+}
+
+fn register_seed(registry: &SeedRegistryClient, seed_id: &str, batch: &str) -> u128 {
+    let env = &registry.env;
+    registry.register_seed(
+        &String::from_str(env, seed_id),
+        &String::from_str(env, "Indica"),
+        &String::from_str(env, batch),
+        &String::from_str(env, "Colombia"),
+        &String::from_str(env, "Bank-001"),
+        &None,
+        &None,
+        &false,
+    )
+}
+
+#[test]
+fn test_recall_by_batch_through_registry() {
+    let (env, client, admin) = setup();
+    let owner = Address::generate(&env);
+    let registry = setup_registry(&env, &client, &admin);
+    
+    for (seed_id, batch) in [("SEED-001", "BATCH-2024-001"), ("SEED-002", "BATCH-2024-001"), ("SEED-003", "BATCH-2024-002")] {
+        let nft_id = register_seed(&registry, seed_id, batch);
+        mint_plant(&env, &client, &admin, &owner, nft_id);
+    }
+    
+    let reason = String::from_str(&env, "Contaminated seed batch");
+    let batch = String::from_str(&env, "BATCH-2024-001");
+    let recall_id = client.recall(&admin, &RecallScope::Batch(batch), &reason);
+    
+    assert_eq!(client.get_recall(&recall_id).unwrap().affected, Vec::from_array(&env, [1u128, 2u128]));
+    assert!(client.is_frozen(&1));
+    assert!(client.is_frozen(&2));
+    assert!(!client.is_frozen(&3));
+}
+
+#[test]
+fn test_registry_reads_licenses_from_nft_contract() {
+    let (env, client, admin) = setup();
+    let registry = setup_registry(&env, &client, &admin);
+    
+    assert!(registry.has_active_license(&registry.address, &RegistryLicenseType::Cultivation));
+    assert!(!registry.has_active_license(&registry.address, &RegistryLicenseType::Retail));
+    assert_eq!(register_seed(&registry, "SEED-001", "BATCH-2024-001"), 1);
+    
+    client.set_license_status(&admin, &registry.address, &LicenseType::Cultivation, &LicenseStatus::Suspended);
+    assert!(!registry.has_active_license(&registry.address, &RegistryLicenseType::Cultivation));
+    let result = registry.try_register_seed(
+        &String::from_str(&env, "SEED-002"),
+        &String::from_str(&env, "Indica"),
+        &String::from_str(&env, "BATCH-2024-001"),
+        &String::from_str(&env, "Colombia"),
+        &String::from_str(&env, "Bank-001"),
+        &None,
+        &None,
+        &false,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_registry_ids_do_not_collide_with_clones() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    let registry = setup_registry(&env, &client, &admin);
+    
+    let mother_id = register_seed(&registry, "SEED-001", "BATCH-2024-001");
+    mint_plant(&env, &client, &admin, &cultivator, mother_id);
+    client.update_state(&cultivator, &mother_id, &(LifecycleState::Germinated as u32), &None, &None, &None, &None);
+    client.update_state(&cultivator, &mother_id, &(LifecycleState::PlantVegetative as u32), &None, &None, &None, &None);
+    let clone_id = client.clone_from(&cultivator, &mother_id, &1, &cultivator).get(0).unwrap();
+    
+    let seed_nft_id = register_seed(&registry, "SEED-002", "BATCH-2024-001");
+    assert_ne!(seed_nft_id, clone_id);
+    assert!(client
+        .try_mint(
+            &cultivator,
+            &clone_id,
+            &String::from_str(&env, "Plant"),
+            &String::from_str(&env, "Cannabis plant"),
+            &String::from_str(&env, "ipfs://plant"),
+            &None,
+            &Vec::new(&env),
+        )
+        .is_err());
+    assert_eq!(client.get_parent(&clone_id), Some(mother_id));
+    mint_plant(&env, &client, &admin, &cultivator, seed_nft_id);
+}
+
+#[test]
+fn test_registry_freeze_reaches_tokens() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    let registry = setup_registry(&env, &client, &admin);
+    
+    let nft_id = register_seed(&registry, "SEED-001", "BATCH-2024-001");
+    mint_plant(&env, &client, &admin, &cultivator, nft_id);
+    harvest(&client, &cultivator, nft_id, 300);
+    let lots = Vec::from_array(
+        &env,
+        [LotSpec { product_type: String::from_str(&env, "flower"), quantity: 300 }],
+    );
+    let lot_id = client.split(&cultivator, &nft_id, &lots).get(0).unwrap();
+    
+    let seed_id = String::from_str(&env, "SEED-001");
+    registry.freeze_seed(&admin, &seed_id, &String::from_str(&env, "Seed bank audit"));
+    assert!(registry.is_seed_frozen(&seed_id));
+    assert!(client.is_frozen(&nft_id));
+    assert!(client.is_frozen(&lot_id));
+    assert_eq!(client.get_freeze(&lot_id).unwrap().frozen_by, registry.address);
+    
+    client.unfreeze_token(&admin, &lot_id);
+    client.freeze_token(&admin, &lot_id, &String::from_str(&env, "Inspection hold"));
+    registry.unfreeze_seed(&admin, &seed_id);
+    assert!(!client.is_frozen(&nft_id));
+    assert!(client.is_frozen(&lot_id));
+}
+
+#[test]
+fn test_provenance_reads_registry_seed() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    let registry = setup_registry(&env, &client, &admin);
+    
+    let nft_id = register_seed(&registry, "SEED-001", "BATCH-2024-001");
+    mint_plant(&env, &client, &admin, &cultivator, nft_id);
+    client.update_state(&cultivator, &nft_id, &(LifecycleState::Germinated as u32), &None, &None, &None, &None);
+    client.update_state(&cultivator, &nft_id, &(LifecycleState::PlantVegetative as u32), &None, &None, &None, &None);
+    let clone_id = client.clone_from(&cultivator, &nft_id, &1, &cultivator).get(0).unwrap();
+    
+    let seed_id = String::from_str(&env, "SEED-001");
+    assert_eq!(registry.get_seed_by_nft_id(&nft_id).unwrap().id, seed_id);
+    assert!(registry.get_seed_by_nft_id(&clone_id).is_none());
+    registry.reindex_seeds(&admin, &Vec::from_array(&env, [seed_id.clone()]));
+    assert!(registry
+        .try_reindex_seeds(&admin, &Vec::from_array(&env, [String::from_str(&env, "SEED-404")]))
+        .is_err());
+    
+    let provenance = client.get_provenance(&clone_id);
+    assert_eq!(provenance.ancestors.len(), 1);
+    assert_eq!(provenance.origin_seeds.len(), 1);
+    let seed = provenance.origin_seeds.get(0).unwrap();
+    assert_eq!(seed.id, seed_id);
+    assert_eq!(seed.batch, String::from_str(&env, "BATCH-2024-001"));
+    assert_eq!(seed.nft_id, nft_id);
+    assert_eq!(seed.nft_contract, client.address);
+}
+
+#[test]
+fn test_regulator_role() {
+    let (env, client, admin) = setup();
+    let regulator = Address::generate(&env);
+    let owner = Address::generate(&env);
+    client.grant_role(&admin, &regulator, &ROLE_REGULATOR);
+    mint_plant(&env, &client, &admin, &owner, 1);
+    
+    assert!(client.try_grant_role(&regulator, &owner, &ROLE_CULTIVATOR).is_err());
+    assert!(client.try_freeze_token(&owner, &1, &String::from_str(&env, "Audit")).is_err());
+    
+    let reason = String::from_str(&env, "Inspection hold");
+    client.freeze_token(&regulator, &1, &reason);
+    assert!(client.is_frozen(&1));
+    
+    let record = client.get_compliance_record(&regulator, &1);
+    assert_eq!(record.freeze_reason, Some(reason));
+    assert_eq!(record.frozen_by, Some(regulator.clone()));
+    assert_eq!(record.lab_results.len(), 0);
+    assert!(client.try_get_compliance_record(&owner, &1).is_err());
+    
+    client.unfreeze_token(&regulator, &1);
+    assert!(!client.is_frozen(&1));
+}
+
+#[test]
+fn test_lifecycle_requires_active_license() {
+    let (env, client, admin) = setup();
+    let regulator = Address::generate(&env);
+    let cultivator = Address::generate(&env);
+    let owner = Address::generate(&env);
+    client.grant_role(&admin, &regulator, &ROLE_REGULATOR);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    mint_plant(&env, &client, &admin, &owner, 1);
+    
+    let germinated = LifecycleState::Germinated as u32;
+    assert!(client.try_update_state(&cultivator, &1, &germinated, &None, &None, &None, &None).is_err());
+    
+    issue_license(&client, &regulator, &cultivator, LicenseType::Cultivation);
+    assert!(client.has_active_license(&cultivator, &LicenseType::Cultivation));
+    client.update_state(&cultivator, &1, &germinated, &None, &None, &None, &None);
+    
+    client.set_license_status(&regulator, &cultivator, &LicenseType::Cultivation, &LicenseStatus::Suspended);
+    assert_eq!(client.get_license(&cultivator, &LicenseType::Cultivation).unwrap().status, LicenseStatus::Suspended);
+    let vegetative = LifecycleState::PlantVegetative as u32;
+    assert!(client.try_update_state(&cultivator, &1, &vegetative, &None, &None, &None, &None).is_err());
+}
+
+#[test]
+fn test_purchase_limits() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    let processor = Address::generate(&env);
+    let dispensary = Address::generate(&env);
+    let consumer = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.grant_role(&admin, &processor, &ROLE_PROCESSOR);
+    issue_license(&client, &admin, &processor, LicenseType::Processing);
+    client.grant_role(&admin, &dispensary, &ROLE_DISPENSARY);
+    issue_license(&client, &admin, &dispensary, LicenseType::Retail);
+    
+    let general = String::from_str(&env, "general");
+    let limit = PurchaseLimit {
+        category: general.clone(),
+        unit: QuantityUnit::Grams,
+        max_quantity: 500,
+        window: 86_400,
+    };
+    client.set_purchase_limit(&admin, &limit);
+    assert_eq!(client.get_purchase_limits(), Vec::from_array(&env, [limit]));
+    
+    mint_plant(&env, &client, &admin, &dispensary, 1);
+    harvest(&client, &cultivator, 1, 1000);
+    process_and_distribute(&client, &admin, &processor, &dispensary, &dispensary, 1);
+    
+    attest_consumer(&client, &admin, &consumer);
+    client.sell_partial(&dispensary, &1, &400, &consumer, &false);
+    let allowance = client.remaining_allowance(&consumer).get(0).unwrap();
+    assert_eq!(allowance.purchased, 400);
+    assert_eq!(allowance.remaining, 100);
+    
+    let result = client.try_sell_partial(&dispensary, &1, &200, &consumer, &false);
+    assert_eq!(result, Err(Ok(Error::PurchaseLimitExceeded.into())));
+    
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    assert_eq!(client.remaining_allowance(&consumer).get(0).unwrap().remaining, 500);
+    client.sell_partial(&dispensary, &1, &200, &consumer, &false);
+    
+    let per_unit = PurchaseLimit {
+        category: general.clone(),
+        unit: QuantityUnit::Units,
+        max_quantity: 1,
+        window: u64::MAX,
+    };
+    client.set_purchase_limit(&admin, &per_unit);
+    assert_eq!(client.get_purchase_limits().len(), 2);
+    client.sell_partial(&dispensary, &1, &100, &consumer, &false);
+    let allowances = client.remaining_allowance(&consumer);
+    assert_eq!(allowances.get(0).unwrap().purchased, 300);
+    assert_eq!(allowances.get(1).unwrap().purchased, 0);
+    
+    client.remove_purchase_limit(&admin, &general, &QuantityUnit::Grams);
+    assert_eq!(client.get_purchase_limits(), Vec::from_array(&env, [per_unit]));
+    client.sell_partial(&dispensary, &1, &300, &consumer, &false);
+    assert!(client.try_remove_purchase_limit(&admin, &general, &QuantityUnit::Grams).is_err());
+}
+
+#[test]
+fn test_consumer_attestations() {
+    let (env, client, admin) = setup();
+    let attester = Address::generate(&env);
+    let cultivator = Address::generate(&env);
+    let processor = Address::generate(&env);
+    let dispensary = Address::generate(&env);
+    let consumer = Address::generate(&env);
+    let owner = Address::generate(&env);
+    client.grant_role(&admin, &attester, &ROLE_ATTESTER);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.grant_role(&admin, &processor, &ROLE_PROCESSOR);
+    issue_license(&client, &admin, &processor, LicenseType::Processing);
+    client.grant_role(&admin, &dispensary, &ROLE_DISPENSARY);
+    issue_license(&client, &admin, &dispensary, LicenseType::Retail);
+    
+    mint_plant(&env, &client, &admin, &owner, 1);
+    harvest(&client, &cultivator, 1, 100);
+    process_and_distribute(&client, &admin, &processor, &dispensary, &owner, 1);
+    
+    client.add_to_whitelist(&admin, &consumer, &WhitelistCategory::Consumer, &None);
+    assert!(!client.has_valid_attestation(&consumer));
+    assert_eq!(
+        client.try_transfer(&owner, &consumer, &1),
+        Err(Ok(Error::MissingAttestation.into()))
+    );
+    
+    let restock = Address::generate(&env);
+    client.grant_role(&admin, &restock, &ROLE_DISPENSARY);
+    client.add_to_whitelist(&admin, &restock, &WhitelistCategory::Business, &None);
+    issue_license(&client, &admin, &restock, LicenseType::Retail);
+    client.transfer(&owner, &restock, &1);
+    assert_eq!(client.owner_of(&1), restock);
+    
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let attestation = Attestation {
+        attester: attester.clone(),
+        attestation_type: AttestationType::MedicalPatient,
+        expires_at: 2_000,
+        evidence_hash: BytesN::from_array(&env, &[9u8; 32]),
+    };
+    let outsider = Address::generate(&env);
+    assert!(client.try_attest(&attester, &outsider, &attestation).is_err());
+    assert!(!client.is_whitelisted(&outsider));
+    assert_eq!(client.get_attestation(&outsider), None);
+    
+    client.attest(&attester, &consumer, &attestation);
+    assert_eq!(client.get_attestation(&consumer), Some(attestation.clone()));
+    client.transfer(&restock, &consumer, &1);
+    
+    let visitor = Address::generate(&env);
+    client.add_to_whitelist(&admin, &visitor, &WhitelistCategory::Consumer, &Some(1_500));
+    client.attest(&attester, &visitor, &attestation);
+    assert!(client.has_valid_attestation(&visitor));
+    env.ledger().with_mut(|li| li.timestamp = 1_500);
+    assert!(!client.has_valid_attestation(&visitor));
+    assert!(client.has_valid_attestation(&consumer));
+    assert!(client.try_attest(&attester, &visitor, &attestation).is_err());
+    
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    assert!(!client.has_valid_attestation(&consumer));
+    let consumed = LifecycleState::Consumed as u32;
+    let result = client.try_update_state(&dispensary, &1, &consumed, &None, &None, &None, &None);
+    assert_eq!(result, Err(Ok(Error::MissingAttestation.into())));
+}
+
+#[test]
+fn test_whitelist_categories_and_expiry() {
+    let (env, client, admin) = setup();
+    let lab = Address::generate(&env);
+    
+    let businesses = Vec::from_array(
+        &env,
+        [Address::generate(&env), Address::generate(&env), Address::generate(&env)],
+    );
+    client.add_to_whitelist_batch(&admin, &businesses, &WhitelistCategory::Business, &None);
+    client.add_to_whitelist(&admin, &lab, &WhitelistCategory::Lab, &Some(1_000));
+    
+    assert_eq!(client.list_whitelist(&WhitelistCategory::Business, &0, &10), businesses);
+    assert_eq!(client.list_whitelist(&WhitelistCategory::Business, &1, &1).len(), 1);
+    assert_eq!(client.list_whitelist(&WhitelistCategory::Lab, &0, &10), Vec::from_array(&env, [lab.clone()]));
+    assert_eq!(client.get_whitelist_entry(&lab).unwrap().category, WhitelistCategory::Lab);
+    
+    assert!(client.is_whitelisted(&lab));
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    assert!(!client.is_whitelisted(&lab));
+    assert_eq!(client.list_whitelist(&WhitelistCategory::Lab, &0, &10).len(), 0);
+    
+    client.remove_from_whitelist(&admin, &businesses.get(0).unwrap());
+    assert_eq!(
+        client.list_whitelist(&WhitelistCategory::Business, &0, &10),
+        Vec::from_array(&env, [businesses.get(2).unwrap(), businesses.get(1).unwrap()])
+    );
+    client.add_to_whitelist(&admin, &businesses.get(1).unwrap(), &WhitelistCategory::Consumer, &None);
+    assert_eq!(client.list_whitelist(&WhitelistCategory::Business, &0, &10), Vec::from_array(&env, [businesses.get(2).unwrap()]));
+    assert_eq!(client.list_whitelist(&WhitelistCategory::Consumer, &0, &10), Vec::from_array(&env, [businesses.get(1).unwrap()]));
+    
+    client.remove_from_whitelist_batch(&admin, &businesses);
+    assert_eq!(client.list_whitelist(&WhitelistCategory::Business, &0, &10).len(), 0);
+    assert_eq!(client.list_whitelist(&WhitelistCategory::Consumer, &0, &10).len(), 0);
+    assert!(!client.is_whitelisted(&businesses.get(0).unwrap()));
+}
+
+#[test]
+fn test_legacy_whitelist_entries() {
+    let (env, client, admin) = setup();
+    let legacy = Address::generate(&env);
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("WHITELIST"), legacy.clone()), &true);
+    });
+    
+    assert!(client.is_whitelisted(&legacy));
+    assert_eq!(client.get_whitelist_entry(&legacy).unwrap().category, WhitelistCategory::Business);
+    assert_eq!(client.list_whitelist(&WhitelistCategory::Business, &0, &10).len(), 0);
+    
+    client.migrate_whitelist(&admin, &Vec::from_array(&env, [legacy.clone()]));
+    assert_eq!(client.list_whitelist(&WhitelistCategory::Business, &0, &10), Vec::from_array(&env, [legacy.clone()]));
+    client.migrate_whitelist(&admin, &Vec::from_array(&env, [legacy.clone()]));
+    assert_eq!(client.list_whitelist(&WhitelistCategory::Business, &0, &10).len(), 1);
+    
+    client.remove_from_whitelist(&admin, &legacy);
+    assert!(!client.is_whitelisted(&legacy));
+}
+
+#[test]
+fn test_state_aware_transfer_rules() {
+    let (env, client, admin) = setup();
+    let owner = Address::generate(&env);
+    let cultivator = Address::generate(&env);
+    let consumer = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    client.add_to_whitelist(&admin, &cultivator, &WhitelistCategory::Business, &None);
+    client.add_to_whitelist(&admin, &consumer, &WhitelistCategory::Consumer, &None);
+    mint_plant(&env, &client, &admin, &owner, 1);
+    
+    let seed = LifecycleState::Seed as u32;
+    assert!(client.get_transfer_rule(&seed).roles.contains(ROLE_CULTIVATOR));
+    assert_eq!(
+        client.try_transfer(&owner, &consumer, &1),
+        Err(Ok(Error::RecipientNotAllowed.into()))
+    );
+    
+    client.transfer(&owner, &cultivator, &1);
+    assert_eq!(client.owner_of(&1), cultivator);
+    
+    let rule = TransferRule {
+        roles: Vec::new(&env),
+        categories: Vec::from_array(&env, [WhitelistCategory::Consumer]),
+    };
+    client.set_transfer_rule(&admin, &seed, &rule);
+    assert_eq!(client.get_transfer_rule(&seed), rule);
+    client.transfer(&cultivator, &consumer, &1);
+    assert_eq!(client.owner_of(&1), consumer);
+}
+
+#[test]
+fn test_custody_in_history() {
+    let (env, client, admin) = setup();
+    let owner = Address::generate(&env);
+    let cultivator = Address::generate(&env);
+    let partner = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.add_to_whitelist(&admin, &cultivator, &WhitelistCategory::Business, &None);
+    client.add_to_whitelist(&admin, &partner, &WhitelistCategory::Business, &None);
+    mint_plant(&env, &client, &admin, &owner, 1);
+    
+    env.ledger().with_mut(|li| li.timestamp = 500);
+    client.transfer(&owner, &cultivator, &1);
+    client.update_state(&cultivator, &1, &(LifecycleState::Germinated as u32), &None, &None, &None, &None);
+    let manifest = String::from_str(&env, "MANIFEST-0042");
+    client.transfer_with_manifest(&cultivator, &partner, &1, &manifest);
+    
+    let history = client.get_history(&1);
+    assert_eq!(history.len(), 4);
+    assert!(matches!(history.get(2).unwrap(), HistoryEntry::Transition(_)));
+    
+    let custody = client.get_custody(&1);
+    assert_eq!(custody.len(), 3);
+    let minted = custody.get(0).unwrap();
+    assert_eq!(minted.from, None);
+    assert_eq!(minted.to, Some(owner.clone()));
+    let first = custody.get(1).unwrap();
+    assert_eq!(first.from, Some(owner.clone()));
+    assert_eq!(first.to, Some(cultivator));
+    assert_eq!(first.operator, owner);
+    assert_eq!(first.timestamp, 500);
+    assert_eq!(first.manifest, None);
+    assert_eq!(custody.get(2).unwrap().manifest, Some(manifest));
+}
+
+#[test]
+fn test_witnessed_destruction() {
+    let (env, client, admin) = setup();
+    let owner = Address::generate(&env);
+    let cultivator = Address::generate(&env);
+    let regulator = Address::generate(&env);
+    let witness = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.grant_role(&admin, &regulator, &ROLE_REGULATOR);
+    mint_plant(&env, &client, &admin, &owner, 1);
+    mint_plant(&env, &client, &admin, &owner, 2);
+    harvest(&client, &cultivator, 1, 100);
+    
+    let witnesses = Vec::from_array(&env, [witness.clone(), regulator.clone()]);
+    assert!(client
+        .try_record_destruction(&owner, &1, &40, &DestructionMethod::Composting, &Vec::new(&env))
+        .is_err());
+    assert!(client
+        .try_record_destruction(&owner, &1, &40, &DestructionMethod::Composting, &Vec::from_array(&env, [owner.clone()]))
+        .is_err());
+    assert!(client
+        .try_record_destruction(&witness, &1, &40, &DestructionMethod::Composting, &Vec::from_array(&env, [owner.clone()]))
+        .is_err());
+    
+    client.record_destruction(&owner, &1, &40, &DestructionMethod::Composting, &witnesses);
+    let lot = client.get_metadata(&1).unwrap();
+    assert_eq!(lot.quantity, Some(60));
+    assert_eq!(lot.state, LifecycleState::PlantHarvested);
+    
+    client.record_destruction(&owner, &1, &60, &DestructionMethod::Incineration, &witnesses);
+    assert_eq!(client.get_metadata(&1).unwrap().state, LifecycleState::Destroyed);
+    assert_eq!(client.get_mass_balance(&1).unwrap().wasted, 100);
+    let destructions = client.get_destructions(&1);
+    assert_eq!(destructions.len(), 2);
+    assert_eq!(destructions.get(0).unwrap().witnesses, witnesses);
+    assert_eq!(destructions.get(1).unwrap().remaining, 0);
+    assert_eq!(destructions.get(1).unwrap().unit, QuantityUnit::Grams);
+    let disposal = client.get_custody(&1).last().unwrap();
+    assert_eq!(disposal.from, Some(owner.clone()));
+    assert_eq!(disposal.to, None);
+    
+    let grinding = Vec::from_array(&env, [witness.clone()]);
+    assert!(client
+        .try_record_destruction(&regulator, &2, &250, &DestructionMethod::Grinding, &grinding)
+        .is_err());
+    harvest(&client, &cultivator, 2, 250);
+    assert!(client
+        .try_record_destruction(&regulator, &2, &251, &DestructionMethod::Grinding, &grinding)
+        .is_err());
+    client.record_destruction(&regulator, &2, &250, &DestructionMethod::Grinding, &grinding);
+    assert_eq!(client.get_metadata(&2).unwrap().state, LifecycleState::Destroyed);
+    assert!(client
+        .try_record_destruction(&regulator, &2, &1, &DestructionMethod::Grinding, &Vec::from_array(&env, [witness.clone()]))
+        .is_err());
+    
+    mint_plant(&env, &client, &admin, &owner, 3);
+    mint_plant(&env, &client, &admin, &owner, 4);
+    harvest(&client, &cultivator, 4, 10);
+    assert!(client
+        .try_record_destruction(&owner, &4, &0, &DestructionMethod::Composting, &witnesses)
+        .is_err());
+    client.record_destruction(&owner, &3, &0, &DestructionMethod::Composting, &witnesses);
+    let seed = client.get_metadata(&3).unwrap();
+    assert_eq!(seed.state, LifecycleState::Destroyed);
+    assert_eq!(seed.quantity, None);
+    let destruction = client.get_destructions(&3).get(0).unwrap();
+    assert_eq!(destruction.quantity, 1);
+    assert_eq!(destruction.unit, QuantityUnit::Units);
+    assert_eq!(client.get_custody(&3).last().unwrap().to, None);
+}
+
+#[test]
+fn test_provenance_report() {
+    let (env, client, admin) = setup();
+    let cultivator = Address::generate(&env);
+    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &cultivator, LicenseType::Cultivation);
+    client.add_to_whitelist(&admin, &cultivator, &WhitelistCategory::Business, &None);
+    mint_plant(&env, &client, &admin, &cultivator, 1);
+    client.update_state(&cultivator, &1, &(LifecycleState::Germinated as u32), &None, &None, &None, &None);
+    client.update_state(&cultivator, &1, &(LifecycleState::PlantVegetative as u32), &None, &None, &None, &None);
+    
+    let clone_id = client.clone_from(&cultivator, &1, &1, &cultivator).get(0).unwrap();
+    let grandchild_id = client.clone_from(&cultivator, &clone_id, &1, &cultivator).get(0).unwrap();
+    client.freeze_token(&admin, &grandchild_id, &String::from_str(&env, "Inspection"));
+    
+    let provenance = client.get_provenance(&grandchild_id);
+    assert_eq!(provenance.token.token_id, grandchild_id);
+    assert_eq!(provenance.token.history.len(), 2);
+    assert_eq!(provenance.ancestors.len(), 2);
+    assert_eq!(provenance.ancestors.get(0).unwrap().token_id, clone_id);
+    assert_eq!(provenance.ancestors.get(1).unwrap().token_id, 1);
+    assert_eq!(provenance.ancestors.get(1).unwrap().history.len(), 3);
+    assert_eq!(provenance.origin_seeds.len(), 0);
+    assert!(provenance.compliance.frozen);
+    assert!(!provenance.compliance.expired);
+    assert!(!provenance.compliance.locked);
+}
+
+#[test]
+fn test_export_and_import() {
+    let (env, client, admin) = setup();
+    let owner = Address::generate(&env);
+    let partner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let regulator = Address::generate(&env);
+    let customs = Address::generate(&env);
+    let canada = String::from_str(&env, "CA");
+    let germany = String::from_str(&env, "DE");
+    client.grant_role(&admin, &regulator, &ROLE_REGULATOR);
+    client.grant_role(&admin, &customs, &ROLE_CUSTOMS);
+    client.grant_role(&admin, &partner, &ROLE_CULTIVATOR);
+    issue_license(&client, &admin, &partner, LicenseType::Cultivation);
+    client.set_home_country(&admin, &canada);
+    client.set_account_country(&admin, &owner, &canada);
+    client.set_account_country(&admin, &partner, &germany);
+    client.set_account_country(&admin, &buyer, &germany);
+    for account in [&partner, &buyer, &stranger] {
+        client.add_to_whitelist(&admin, account, &WhitelistCategory::Business, &None);
+    }
+    mint_plant(&env, &client, &admin, &owner, 1);
+    
+    assert_eq!(client.get_token_country(&1), Some(canada.clone()));
+    assert_eq!(client.try_transfer(&owner, &stranger, &1), Err(Ok(Error::UnknownJurisdiction.into())));
+    assert_eq!(
+        client.try_transfer(&owner, &partner, &1),
+        Err(Ok(Error::MissingExportPermit.into()))
+    );
+    
+    let permit = ExportPermit {
+        permit_number: String::from_str(&env, "EXP-2026-001"),
+        issued_by: regulator.clone(),
+        origin_country: canada.clone(),
+        destination_country: germany.clone(),
+        expires_at: 10_000,
+    };
+    let clearance = CustomsClearance {
+        authority: customs.clone(),
+        reference: String::from_str(&env, "CBSA-104"),
+        document_hash: BytesN::from_array(&env, &[6u8; 32]),
+        cleared_at: 0,
+    };
+    let mismatched = ExportPermit {
+        origin_country: String::from_str(&env, "US"),
+        ..permit.clone()
+    };
+    let self_issued = ExportPermit {
+        issued_by: owner.clone(),
+        ..permit.clone()
+    };
+    let self_cleared = CustomsClearance {
+        authority: owner.clone(),
+        ..clearance.clone()
+    };
+    assert!(client.try_record_export(&owner, &1, &mismatched, &clearance).is_err());
+    assert!(client.try_record_export(&owner, &1, &self_issued, &clearance).is_err());
+    assert!(client.try_record_export(&owner, &1, &permit, &self_cleared).is_err());
+    client.record_export(&owner, &1, &permit, &clearance);
+    assert_eq!(client.get_pending_export(&1), Some(permit.clone()));
+    client.transfer(&owner, &partner, &1);
+    assert_eq!(
+        client.try_transfer(&partner, &buyer, &1),
+        Err(Ok(Error::MissingExportPermit.into()))
+    );
+    
+    let import = CustomsClearance {
+        authority: customs,
+        reference: String::from_str(&env, "ZOLL-778"),
+        document_hash: BytesN::from_array(&env, &[7u8; 32]),
+        cleared_at: 0,
+    };
+    assert!(client
+        .try_record_import(&partner, &1, &String::from_str(&env, "EXP-OTHER"), &import)
+        .is_err());
+    assert!(client
+        .try_record_import(&partner, &1, &permit.permit_number, &self_cleared)
+        .is_err());
+    client.freeze_token(&admin, &1, &String::from_str(&env, "Inspection"));
+    assert!(client.try_record_import(&partner, &1, &permit.permit_number, &import).is_err());
+    client.unfreeze_token(&admin, &1);
+    client.record_import(&partner, &1, &permit.permit_number, &import);
+    
+    assert_eq!(client.get_token_country(&1), Some(germany.clone()));
+    assert_eq!(client.get_pending_export(&1), None);
+    let crossings = client.get_border_crossings(&1);
+    assert_eq!(crossings.len(), 2);
+    assert_eq!(crossings.get(0).unwrap().kind, CrossingKind::Export);
+    assert_eq!(crossings.get(0).unwrap().issued_by, regulator);
+    assert_eq!(crossings.get(1).unwrap().clearance, import);
+    
+    client.update_state(&partner, &1, &(LifecycleState::Germinated as u32), &None, &None, &None, &None);
+    client.update_state(&partner, &1, &(LifecycleState::PlantVegetative as u32), &None, &None, &None, &None);
+    assert_eq!(
+        client.try_clone_from(&partner, &1, &1, &owner),
+        Err(Ok(Error::MissingExportPermit.into()))
+    );
+    let clone_id = client.clone_from(&partner, &1, &1, &buyer).get(0).unwrap();
+    assert_eq!(client.get_token_country(&clone_id), Some(germany));
+}
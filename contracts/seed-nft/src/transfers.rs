@@ -0,0 +1,82 @@
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
+use crate::lifecycle::LifecycleState;
+use crate::nft::SeedNFT;
+use crate::roles::{ROLE_ADMIN, ROLE_CULTIVATOR, ROLE_DISPENSARY, ROLE_PROCESSOR};
+use crate::whitelist::WhitelistCategory;
+use crate::Error;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransferRule {
+    pub roles: Vec<Symbol>,
+    pub categories: Vec<WhitelistCategory>,
+}
+
+pub struct TransferRules;
+
+impl TransferRules {
+    const RULES: Symbol = symbol_short!("XFER_RULE");
+
+    pub fn set(env: &Env, caller: &Address, state: LifecycleState, rule: &TransferRule) {
+        SeedNFT::require_role(env, caller, ROLE_ADMIN);
+        env.storage()
+            .persistent()
+            .set(&(Self::RULES, state), rule);
+    }
+
+    pub fn get(env: &Env, state: LifecycleState) -> TransferRule {
+        env.storage()
+            .persistent()
+            .get(&(Self::RULES, state))
+            .unwrap_or_else(|| Self::default_for(env, state))
+    }
+
+    pub fn check(env: &Env, state: LifecycleState, to: &Address) -> Result<(), Error> {
+        let rule = Self::get(env, state);
+
+        for role in rule.roles.iter() {
+            if SeedNFT::has_role(env, to, role) {
+                return Ok(());
+            }
+        }
+        if let Some(entry) = SeedNFT::get_whitelist_entry(env, to) {
+            if rule.categories.contains(entry.category) {
+                return Ok(());
+            }
+        }
+
+        Err(Error::RecipientNotAllowed)
+    }
+
+    fn default_for(env: &Env, state: LifecycleState) -> TransferRule {
+        let mut roles = Vec::new(env);
+        let mut categories = Vec::new(env);
+
+        match state {
+            LifecycleState::Seed
+            | LifecycleState::Germinated
+            | LifecycleState::PlantVegetative
+            | LifecycleState::PlantFlowering
+            | LifecycleState::PlantHarvested => {
+                roles.push_back(ROLE_CULTIVATOR);
+                categories.push_back(WhitelistCategory::Business);
+            }
+            LifecycleState::Processed => {
+                roles.push_back(ROLE_PROCESSOR);
+                roles.push_back(ROLE_DISPENSARY);
+                categories.push_back(WhitelistCategory::Business);
+            }
+            LifecycleState::Distributed => {
+                roles.push_back(ROLE_DISPENSARY);
+                categories.push_back(WhitelistCategory::Patient);
+                categories.push_back(WhitelistCategory::Consumer);
+            }
+            LifecycleState::Consumed
+            | LifecycleState::Split
+            | LifecycleState::Merged
+            | LifecycleState::Destroyed => {}
+        }
+
+        TransferRule { roles, categories }
+    }
+}
@@ -0,0 +1,33 @@
+use soroban_sdk::{contracttype, Address, BytesN};
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WhitelistCategory {
+    Business = 0,
+    Patient = 1,
+    Consumer = 2,
+    Lab = 3,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AttestationType {
+    Age21Plus = 0,
+    MedicalPatient = 1,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attestation {
+    pub attester: Address,
+    pub attestation_type: AttestationType,
+    pub expires_at: u64,
+    pub evidence_hash: BytesN<32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WhitelistEntry {
+    pub category: WhitelistCategory,
+    pub expires_at: Option<u64>,
+}
@@ -1,15 +1,20 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracterror, contractevent, symbol_short,
-    Address, Env, String, Vec, Symbol,
+    contract, contractimpl, contracterror, contractevent, symbol_short, vec,
+    Address, BytesN, Env, IntoVal, String, Vec, Symbol,
 };
+use seed_common::admin::Admins;
 use crate::registry::Registry;
 use crate::seed_data::SeedData;
 use crate::roles::{ROLE_ADMIN, ROLE_CULTIVATOR};
+use crate::license::{LicenseType, Licenses};
+use crate::seed_bank::{SeedBank, SeedBankStatus, SeedBanks};
 
 mod registry;
-mod seed_data;
+pub use seed_common::seed_data;
 mod roles;
+pub mod license;
+pub mod seed_bank;
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -36,6 +41,49 @@ pub struct SeedRegisteredBatchEvent {
     pub nft_id: u128,
 }
 
+#[contractevent]
+pub struct SeedFrozenEvent {
+    pub seed_id: String,
+    pub frozen_by: Address,
+    pub reason: String,
+}
+
+#[contractevent]
+pub struct SeedUnfrozenEvent {
+    pub seed_id: String,
+    pub unfrozen_by: Address,
+}
+
+#[contractevent]
+pub struct SeedBankApprovedEvent {
+    pub id: String,
+    pub country: String,
+    pub approved_by: Address,
+}
+
+#[contractevent]
+pub struct SeedBankStatusEvent {
+    pub id: String,
+    pub status: SeedBankStatus,
+}
+
+#[contractevent]
+pub struct AdminProposedEvent {
+    pub current_admin: Address,
+    pub proposed_admin: Address,
+}
+
+#[contractevent]
+pub struct AdminTransferredEvent {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent]
+pub struct AdminRenouncedEvent {
+    pub account: Address,
+}
+
 #[contractevent]
 pub struct RoleGrantEvent {
     pub account: Address,
@@ -82,11 +130,12 @@ impl SeedRegistry {
         let creator = env.current_contract_address();
         creator.require_auth();
         let creator_clone = creator.clone();
-        Registry::require_role(&env, &creator_clone, ROLE_CULTIVATOR);
+        Registry::require_licensed_role(&env, &creator_clone, ROLE_CULTIVATOR);
 
         if Registry::get_seed_data(&env, &seed_id).is_some() {
             panic!("Seed already exists");
         }
+        SeedBanks::require_active(&env, &seed_bank);
         let created_at = env.ledger().timestamp();
         let nft_contract = Registry::get_nft_contract(&env);
         
@@ -109,6 +158,7 @@ impl SeedRegistry {
             nft_contract: nft_contract.clone(),
         };
 
+        SeedBanks::index_seed(&env, &seed_data.seed_bank, &seed_id);
         Registry::store_seed_data(&env, &seed_id, &seed_data);
         Registry::add_seed_id(&env, &seed_id);
         Registry::increment_seed_count(&env);
@@ -138,7 +188,7 @@ impl SeedRegistry {
         let creator = env.current_contract_address();
         creator.require_auth();
         let creator_clone = creator.clone();
-        Registry::require_role(&env, &creator_clone, ROLE_CULTIVATOR);
+        Registry::require_licensed_role(&env, &creator_clone, ROLE_CULTIVATOR);
 
         let len = seed_ids.len();
         if len != varieties.len()
@@ -166,6 +216,9 @@ impl SeedRegistry {
                 continue;
             }
 
+            let seed_bank = seed_banks.get(i).unwrap();
+            SeedBanks::require_active(&env, &seed_bank);
+
             let creator = env.current_contract_address();
             creator.require_auth();
             let created_at = env.ledger().timestamp();
@@ -178,7 +231,7 @@ impl SeedRegistry {
                 variety: varieties.get(i).unwrap().clone(),
                 batch: batches.get(i).unwrap().clone(),
                 origin_country: origin_countries.get(i).unwrap().clone(),
-                seed_bank: seed_banks.get(i).unwrap().clone(),
+                seed_bank: seed_bank.clone(),
                 expected_thc: expected_thcs.get(i).unwrap().clone(),
                 expected_cbd: expected_cbds.get(i).unwrap().clone(),
                 organic_certified: organic_certified_flags.get(i).unwrap().clone(),
@@ -186,6 +239,7 @@ impl SeedRegistry {
                 nft_contract: nft_contract.clone(),
             };
 
+            SeedBanks::index_seed(&env, &seed_bank, &seed_id);
             Registry::store_seed_data(&env, &seed_id, &seed_data);
             Registry::add_seed_id(&env, &seed_id);
             Registry::increment_seed_count(&env);
@@ -202,13 +256,15 @@ impl SeedRegistry {
 
     fn create_nft(
         env: &Env,
-        _nft_contract: &Address,
+        nft_contract: &Address,
         _seed_id: &String,
         _creator: &Address,
     ) -> u128 {
-        let nft_id = (Registry::get_seed_count(env) + 1) as u128;
-        
-        nft_id
+        env.invoke_contract(
+            nft_contract,
+            &Symbol::new(env, "reserve_token_id"),
+            vec![env],
+        )
     }
 
     pub fn get_seed(env: Env, seed_id: String) -> Option<SeedData> {
@@ -255,6 +311,10 @@ impl SeedRegistry {
         result
     }
 
+    pub fn get_seeds_by_seed_bank(env: Env, seed_bank: String) -> Vec<String> {
+        SeedBanks::get_seeds(&env, &seed_bank)
+    }
+
     pub fn query_seeds_by_creator(env: Env, creator: Address) -> Vec<String> {
         let all_ids = Registry::get_seed_ids(&env);
         let mut result = Vec::new(&env);
@@ -271,6 +331,65 @@ impl SeedRegistry {
         result
     }
 
+    pub fn get_nft_ids_by_batch(env: Env, batch: String) -> Vec<u128> {
+        let all_ids = Registry::get_seed_ids(&env);
+        let mut result = Vec::new(&env);
+        
+        for i in 0..all_ids.len() {
+            let seed_id = all_ids.get(i).unwrap();
+            if let Some(seed_data) = Registry::get_seed_data(&env, &seed_id) {
+                if seed_data.batch == batch {
+                    result.push_back(seed_data.nft_id);
+                }
+            }
+        }
+        
+        result
+    }
+
+    pub fn get_nft_ids_by_seed_bank(env: Env, seed_bank: String) -> Vec<u128> {
+        let seed_ids = SeedBanks::get_seeds(&env, &seed_bank);
+        let mut result = Vec::new(&env);
+        
+        for i in 0..seed_ids.len() {
+            let seed_id = seed_ids.get(i).unwrap();
+            if let Some(seed_data) = Registry::get_seed_data(&env, &seed_id) {
+                result.push_back(seed_data.nft_id);
+            }
+        }
+        
+        result
+    }
+
+    pub fn get_seed_by_nft_id(env: Env, nft_id: u128) -> Option<SeedData> {
+        let seed_id = Registry::get_seed_id_by_nft(&env, nft_id)?;
+        Registry::get_seed_data(&env, &seed_id)
+    }
+
+    pub fn get_nft_ids_by_seed_id(env: Env, seed_id: String) -> Vec<u128> {
+        let mut result = Vec::new(&env);
+        if let Some(seed_data) = Registry::get_seed_data(&env, &seed_id) {
+            result.push_back(seed_data.nft_id);
+        }
+        result
+    }
+
+    /// Rebuilds the lookup indexes for seeds registered before they existed.
+    pub fn reindex_seeds(env: Env, caller: Address, seed_ids: Vec<String>) {
+        caller.require_auth();
+        Registry::require_role(&env, &caller, ROLE_ADMIN);
+        if seed_ids.len() > 100 {
+            panic!("Batch size too large: max 100");
+        }
+
+        for seed_id in seed_ids.iter() {
+            let seed_data = Registry::get_seed_data(&env, &seed_id)
+                .unwrap_or_else(|| panic!("Seed not found"));
+            Registry::store_seed_data(&env, &seed_id, &seed_data);
+            SeedBanks::backfill_seed(&env, &seed_data.seed_bank, &seed_id);
+        }
+    }
+
     pub fn grant_role(env: Env, caller: Address, account: Address, role: Symbol) {
         caller.require_auth();
         Registry::require_role(&env, &caller, ROLE_ADMIN);
@@ -289,6 +408,48 @@ impl SeedRegistry {
         RoleRevokeEvent { account, role: role_clone }.publish(&env);
     }
 
+    pub fn propose_admin(env: Env, caller: Address, new_admin: Address) {
+        caller.require_auth();
+        Admins::propose_admin(&env, &caller, &new_admin);
+        AdminProposedEvent {
+            current_admin: caller,
+            proposed_admin: new_admin,
+        }.publish(&env);
+    }
+
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        new_admin.require_auth();
+        let previous_admin = Admins::accept_admin(&env, &new_admin);
+        AdminTransferredEvent {
+            previous_admin,
+            new_admin,
+        }.publish(&env);
+    }
+
+    pub fn cancel_admin_transfer(env: Env, caller: Address) {
+        caller.require_auth();
+        Admins::cancel_admin_transfer(&env, &caller);
+    }
+
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        Admins::get_pending_admin(&env).map(|(_, new_admin)| new_admin)
+    }
+
+    pub fn renounce_admin(env: Env, caller: Address, confirmation: Symbol) {
+        caller.require_auth();
+        Admins::renounce_admin(&env, &caller, confirmation);
+        AdminRenouncedEvent { account: caller }.publish(&env);
+    }
+
+    pub fn get_admin_count(env: Env) -> u32 {
+        Admins::admin_count(&env)
+    }
+
+    pub fn sync_admin_count(env: Env, caller: Address, admins: Vec<Address>) {
+        caller.require_auth();
+        Admins::sync_admin_count(&env, &caller, &admins);
+    }
+
     pub fn has_role(env: Env, account: Address, role: Symbol) -> bool {
         Registry::has_role(&env, &account, role)
     }
@@ -312,6 +473,86 @@ impl SeedRegistry {
             .unwrap_or(false)
     }
 
+    /// Freezes the seed and, through the NFT contract, its token and every
+    /// token derived from it.
+    pub fn freeze_seed(env: Env, caller: Address, seed_id: String, reason: String) {
+        caller.require_auth();
+        let seed_data = Registry::freeze_seed(&env, &caller, &seed_id);
+        env.invoke_contract::<()>(
+            &seed_data.nft_contract,
+            &Symbol::new(&env, "freeze_seed_tokens"),
+            vec![&env, seed_data.nft_id.into_val(&env), reason.into_val(&env)],
+        );
+        SeedFrozenEvent { seed_id, frozen_by: caller, reason }.publish(&env);
+    }
+
+    pub fn unfreeze_seed(env: Env, caller: Address, seed_id: String) {
+        caller.require_auth();
+        let seed_data = Registry::unfreeze_seed(&env, &caller, &seed_id);
+        env.invoke_contract::<()>(
+            &seed_data.nft_contract,
+            &Symbol::new(&env, "unfreeze_seed_tokens"),
+            vec![&env, seed_data.nft_id.into_val(&env)],
+        );
+        SeedUnfrozenEvent { seed_id, unfrozen_by: caller }.publish(&env);
+    }
+
+    pub fn is_seed_frozen(env: Env, seed_id: String) -> bool {
+        Registry::is_seed_frozen(&env, &seed_id)
+    }
+
+    pub fn approve_seed_bank(env: Env, caller: Address, bank: SeedBank) {
+        caller.require_auth();
+        Registry::require_role(&env, &caller, ROLE_ADMIN);
+        if SeedBanks::get(&env, &bank.id).is_some() {
+            panic!("Seed bank already approved");
+        }
+
+        let mut bank = bank;
+        bank.approved_at = env.ledger().timestamp();
+        SeedBanks::store(&env, &bank);
+
+        SeedBankApprovedEvent {
+            id: bank.id,
+            country: bank.country,
+            approved_by: caller,
+        }.publish(&env);
+    }
+
+    pub fn set_seed_bank_status(env: Env, caller: Address, id: String, status: SeedBankStatus) {
+        caller.require_auth();
+        Registry::require_role(&env, &caller, ROLE_ADMIN);
+
+        let mut bank = SeedBanks::get(&env, &id)
+            .unwrap_or_else(|| panic!("Unknown seed bank"));
+        bank.status = status;
+        SeedBanks::store(&env, &bank);
+
+        SeedBankStatusEvent { id, status }.publish(&env);
+    }
+
+    pub fn add_seed_bank_certification(env: Env, caller: Address, id: String, document_hash: BytesN<32>) {
+        caller.require_auth();
+        Registry::require_role(&env, &caller, ROLE_ADMIN);
+
+        let mut bank = SeedBanks::get(&env, &id)
+            .unwrap_or_else(|| panic!("Unknown seed bank"));
+        bank.certifications.push_back(document_hash);
+        SeedBanks::store(&env, &bank);
+    }
+
+    pub fn get_seed_bank(env: Env, id: String) -> Option<SeedBank> {
+        SeedBanks::get(&env, &id)
+    }
+
+    pub fn list_seed_banks(env: Env) -> Vec<String> {
+        SeedBanks::get_ids(&env)
+    }
+
+    pub fn has_active_license(env: Env, account: Address, license_type: LicenseType) -> bool {
+        Licenses::is_active(&env, &account, license_type)
+    }
+
     pub fn set_nft_contract(env: Env, caller: Address, nft_contract: Address) {
         caller.require_auth();
         Registry::set_nft_contract(&env, &caller, &nft_contract);
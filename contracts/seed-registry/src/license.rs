@@ -0,0 +1,44 @@
+use soroban_sdk::{contracttype, vec, Address, Env, IntoVal, Symbol};
+use crate::registry::Registry;
+use crate::roles::{ROLE_CULTIVATOR, ROLE_DISPENSARY, ROLE_PROCESSOR};
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LicenseType {
+    Cultivation = 0,
+    Processing = 1,
+    Retail = 2,
+    Lab = 3,
+}
+
+/// Licences are issued and stored by the NFT contract; the registry only
+/// asks it whether an account holds one.
+pub struct Licenses;
+
+impl Licenses {
+    pub fn license_type_for_role(role: &Symbol) -> Option<LicenseType> {
+        if *role == ROLE_CULTIVATOR {
+            Some(LicenseType::Cultivation)
+        } else if *role == ROLE_PROCESSOR {
+            Some(LicenseType::Processing)
+        } else if *role == ROLE_DISPENSARY {
+            Some(LicenseType::Retail)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_active(env: &Env, account: &Address, license_type: LicenseType) -> bool {
+        env.invoke_contract(
+            &Registry::get_nft_contract(env),
+            &Symbol::new(env, "has_active_license"),
+            vec![env, account.into_val(env), license_type.into_val(env)],
+        )
+    }
+
+    pub fn require_active(env: &Env, account: &Address, license_type: LicenseType) {
+        if !Self::is_active(env, account, license_type) {
+            panic!("Missing active license");
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use soroban_sdk::{
     symbol_short, Address, Env, String, Symbol, Vec,
 };
+use seed_common::admin::Admins;
 use crate::seed_data::SeedData;
-use crate::roles::{ROLE_ADMIN, get_role_key};
+use crate::license::Licenses;
+use crate::roles::{ROLE_ADMIN, ROLE_REGULATOR};
 
 pub struct Registry;
 
@@ -10,8 +12,10 @@ impl Registry {
     const SEED_COUNT: Symbol = symbol_short!("SEED_CNT");
     const SEED_DATA: Symbol = symbol_short!("SEED_DATA");
     const SEED_IDS: Symbol = symbol_short!("SEED_IDS");
+    const NFT_SEED: Symbol = symbol_short!("NFT_SEED");
     const NFT_CONTRACT: Symbol = symbol_short!("NFT_CNTR");
     const PAUSED: Symbol = symbol_short!("PAUSED");
+    const FROZEN: Symbol = symbol_short!("FROZEN");
 
     pub fn initialize(env: &Env, admin: Address, nft_contract: Address) {
         if env.storage().instance().has(&Self::SEED_COUNT) {
@@ -22,7 +26,7 @@ impl Registry {
         env.storage().instance().set(&Self::NFT_CONTRACT, &nft_contract);
         env.storage().instance().set(&Self::PAUSED, &false);
         
-        Self::grant_role(env, &admin, ROLE_ADMIN);
+        Admins::init(env, &admin);
     }
 
     pub fn require_not_paused(env: &Env) {
@@ -33,35 +37,33 @@ impl Registry {
     }
 
     pub fn require_role(env: &Env, account: &Address, role: Symbol) {
-        let role_key = get_role_key(role);
-        let has_role: bool = env
-            .storage()
-            .persistent()
-            .get(&(role_key, account.clone()))
-            .unwrap_or(false);
-        if !has_role {
+        Admins::require_role(env, account, role);
+    }
+
+    pub fn require_licensed_role(env: &Env, account: &Address, role: Symbol) {
+        let license_type = Licenses::license_type_for_role(&role);
+        Self::require_role(env, account, role);
+        if let Some(license_type) = license_type {
+            Licenses::require_active(env, account, license_type);
+        }
+    }
+
+    pub fn require_regulator(env: &Env, account: &Address) {
+        if !Self::has_role(env, account, ROLE_ADMIN) && !Self::has_role(env, account, ROLE_REGULATOR) {
             panic!("Missing required role");
         }
     }
 
     pub fn grant_role(env: &Env, account: &Address, role: Symbol) {
-        let role_key = get_role_key(role);
-        env.storage()
-            .persistent()
-            .set(&(role_key, account.clone()), &true);
+        Admins::grant_role(env, account, role);
     }
 
     pub fn revoke_role(env: &Env, account: &Address, role: Symbol) {
-        let role_key = get_role_key(role);
-        env.storage().persistent().remove(&(role_key, account.clone()));
+        Admins::revoke_role(env, account, role);
     }
 
     pub fn has_role(env: &Env, account: &Address, role: Symbol) -> bool {
-        let role_key = get_role_key(role);
-        env.storage()
-            .persistent()
-            .get(&(role_key, account.clone()))
-            .unwrap_or(false)
+        Admins::has_role(env, account, role)
     }
 
     pub fn pause(env: &Env, account: &Address) {
@@ -92,6 +94,15 @@ impl Registry {
         env.storage()
             .persistent()
             .set(&(Self::SEED_DATA, seed_id.clone()), data);
+        env.storage()
+            .persistent()
+            .set(&(Self::NFT_SEED, data.nft_id), seed_id);
+    }
+
+    pub fn get_seed_id_by_nft(env: &Env, nft_id: u128) -> Option<String> {
+        env.storage()
+            .persistent()
+            .get(&(Self::NFT_SEED, nft_id))
     }
 
     pub fn get_seed_data(env: &Env, seed_id: &String) -> Option<SeedData> {
@@ -129,5 +140,29 @@ impl Registry {
         Self::require_role(env, account, ROLE_ADMIN);
         env.storage().instance().set(&Self::NFT_CONTRACT, nft_contract);
     }
-}
 
+    pub fn freeze_seed(env: &Env, account: &Address, seed_id: &String) -> SeedData {
+        Self::require_regulator(env, account);
+        let seed_data = Self::get_seed_data(env, seed_id)
+            .unwrap_or_else(|| panic!("Seed not found"));
+        env.storage()
+            .persistent()
+            .set(&(Self::FROZEN, seed_id.clone()), &true);
+        seed_data
+    }
+
+    pub fn unfreeze_seed(env: &Env, account: &Address, seed_id: &String) -> SeedData {
+        Self::require_regulator(env, account);
+        let seed_data = Self::get_seed_data(env, seed_id)
+            .unwrap_or_else(|| panic!("Seed not found"));
+        env.storage().persistent().remove(&(Self::FROZEN, seed_id.clone()));
+        seed_data
+    }
+
+    pub fn is_seed_frozen(env: &Env, seed_id: &String) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(Self::FROZEN, seed_id.clone()))
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,83 @@
+use soroban_sdk::{contracttype, symbol_short, BytesN, Env, String, Symbol, Vec};
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SeedBankStatus {
+    Active = 0,
+    Suspended = 1,
+    Revoked = 2,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SeedBank {
+    pub id: String,
+    pub name: String,
+    pub country: String,
+    pub certifications: Vec<BytesN<32>>,
+    pub status: SeedBankStatus,
+    pub approved_at: u64,
+}
+
+pub struct SeedBanks;
+
+impl SeedBanks {
+    const SEED_BANK: Symbol = symbol_short!("SEED_BANK");
+    const BANK_IDS: Symbol = symbol_short!("BANK_IDS");
+    const BANK_SEED: Symbol = symbol_short!("BANK_SEED");
+
+    pub fn store(env: &Env, bank: &SeedBank) {
+        if Self::get(env, &bank.id).is_none() {
+            let mut ids = Self::get_ids(env);
+            ids.push_back(bank.id.clone());
+            env.storage().persistent().set(&Self::BANK_IDS, &ids);
+        }
+        env.storage()
+            .persistent()
+            .set(&(Self::SEED_BANK, bank.id.clone()), bank);
+    }
+
+    pub fn get(env: &Env, id: &String) -> Option<SeedBank> {
+        env.storage()
+            .persistent()
+            .get(&(Self::SEED_BANK, id.clone()))
+    }
+
+    pub fn get_ids(env: &Env) -> Vec<String> {
+        env.storage()
+            .persistent()
+            .get(&Self::BANK_IDS)
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    pub fn require_active(env: &Env, id: &String) {
+        match Self::get(env, id) {
+            Some(bank) if bank.status == SeedBankStatus::Active => {}
+            Some(_) => panic!("Seed bank not active"),
+            None => panic!("Unknown seed bank"),
+        }
+    }
+
+    pub fn index_seed(env: &Env, id: &String, seed_id: &String) {
+        let mut seeds = Self::get_seeds(env, id);
+        seeds.push_back(seed_id.clone());
+        env.storage()
+            .persistent()
+            .set(&(Self::BANK_SEED, id.clone()), &seeds);
+    }
+
+    /// Adds seeds registered before the bank index existed; seeds already
+    /// listed are left alone.
+    pub fn backfill_seed(env: &Env, id: &String, seed_id: &String) {
+        if !Self::get_seeds(env, id).contains(seed_id) {
+            Self::index_seed(env, id, seed_id);
+        }
+    }
+
+    pub fn get_seeds(env: &Env, id: &String) -> Vec<String> {
+        env.storage()
+            .persistent()
+            .get(&(Self::BANK_SEED, id.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+}
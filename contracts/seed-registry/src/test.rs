@@ -1,20 +1,74 @@
 #![cfg(test)]
 use soroban_sdk::{
-    symbol_short, testutils::Address as _, Address, Env, String, Symbol,
+    contract, contractimpl, symbol_short, testutils::Address as _, Address, BytesN, Env, String, Vec,
 };
 use crate::{SeedRegistry, SeedRegistryClient};
-use crate::roles::{ROLE_ADMIN, ROLE_CULTIVATOR};
+use crate::license::LicenseType;
+use crate::registry::Registry;
+use crate::roles::{ROLE_ADMIN, ROLE_CULTIVATOR, ROLE_REGULATOR};
+use crate::seed_bank::{SeedBank, SeedBankStatus};
+use crate::seed_data::SeedData;
 
-#[test]
-fn test_initialize() {
+/// Stands in for the NFT contract, which holds the licences the registry asks
+/// about.
+#[contract]
+pub struct LicensedNft;
+
+#[contractimpl]
+impl LicensedNft {
+    pub fn has_active_license(_env: Env, _account: Address, _license_type: LicenseType) -> bool {
+        true
+    }
+
+    pub fn reserve_token_id(env: Env) -> u128 {
+        let next: u128 = env.storage().instance().get(&symbol_short!("NEXT_ID")).unwrap_or(1);
+        env.storage().instance().set(&symbol_short!("NEXT_ID"), &(next + 1));
+        next
+    }
+}
+
+fn setup() -> (Env, SeedRegistryClient<'static>, Address) {
     let env = Env::default();
+    env.mock_all_auths();
     let admin = Address::generate(&env);
-    let nft_contract = Address::generate(&env);
+    let nft_contract = env.register(LicensedNft, ());
     
-    let contract_id = env.register_contract(None, SeedRegistry);
+    let contract_id = env.register(SeedRegistry, ());
     let client = SeedRegistryClient::new(&env, &contract_id);
     
     client.initialize(&admin, &nft_contract);
+    client.grant_role(&admin, &contract_id, &ROLE_CULTIVATOR);
+    (env, client, admin)
+}
+
+fn seed_bank(env: &Env, id: &str) -> SeedBank {
+    SeedBank {
+        id: String::from_str(env, id),
+        name: String::from_str(env, "Andes Genetics"),
+        country: String::from_str(env, "CO"),
+        certifications: Vec::from_array(env, [BytesN::from_array(env, &[1u8; 32])]),
+        status: SeedBankStatus::Active,
+        approved_at: 0,
+    }
+}
+
+fn register_seed(client: &SeedRegistryClient, seed_id: &str, seed_bank: &str) -> u128 {
+    let env = &client.env;
+    client.register_seed(
+        &String::from_str(env, seed_id),
+        &String::from_str(env, "Indica"),
+        &String::from_str(env, "BATCH-2024-001"),
+        &String::from_str(env, "Colombia"),
+        &String::from_str(env, seed_bank),
+        &None,
+        &None,
+        &false,
+    )
+}
+
+#[test]
+fn test_initialize() {
+    let (_env, client, admin) = setup();
     
     assert_eq!(client.get_seed_count(), 0);
     assert!(client.has_role(&admin, &ROLE_ADMIN));
@@ -22,41 +76,33 @@ fn test_initialize() {
 
 #[test]
 fn test_register_seed() {
-    let env = Env::default();
-    let admin = Address::generate(&env);
-    let cultivator = Address::generate(&env);
-    let nft_contract = Address::generate(&env);
-    
-    let contract_id = env.register_contract(None, SeedRegistry);
-    let client = SeedRegistryClient::new(&env, &contract_id);
-    
-    client.initialize(&admin, &nft_contract);
-    client.grant_role(&admin, &cultivator, &ROLE_CULTIVATOR);
+    let (env, client, admin) = setup();
+    client.approve_seed_bank(&admin, &seed_bank(&env, "Bank-001"));
     
     let seed_id = String::from_str(&env, "SEED-001");
-    let variety = String::from_str(&env, "Indica");
-    let batch = String::from_str(&env, "BATCH-2024-001");
-    let origin_country = String::from_str(&env, "Colombia");
-    let seed_bank = String::from_str(&env, "Bank-001");
-    
-    env.as_contract(&contract_id, || {
-        env.prng().seed(12345);
-    });
+    let nft_id = register_seed(&client, "SEED-001", "Bank-001");
     
-    assert_eq!(client.get_seed_count(), 0);
+    assert_eq!(client.get_seed_count(), 1);
+    let seed = client.get_seed(&seed_id).unwrap();
+    assert_eq!(seed.variety, String::from_str(&env, "Indica"));
+    assert_eq!(seed.nft_id, nft_id);
+    assert_eq!(client.get_seed_by_nft_id(&nft_id).unwrap().id, seed_id);
+    assert!(client.try_register_seed(
+        &seed_id,
+        &String::from_str(&env, "Indica"),
+        &String::from_str(&env, "BATCH-2024-001"),
+        &String::from_str(&env, "Colombia"),
+        &String::from_str(&env, "Bank-001"),
+        &None,
+        &None,
+        &false,
+    ).is_err());
 }
 
 #[test]
 fn test_roles() {
-    let env = Env::default();
-    let admin = Address::generate(&env);
+    let (env, client, admin) = setup();
     let user = Address::generate(&env);
-    let nft_contract = Address::generate(&env);
-    
-    let contract_id = env.register_contract(None, SeedRegistry);
-    let client = SeedRegistryClient::new(&env, &contract_id);
-    
-    client.initialize(&admin, &nft_contract);
     
     assert!(!client.has_role(&user, &ROLE_CULTIVATOR));
     
@@ -69,14 +115,7 @@ fn test_roles() {
 
 #[test]
 fn test_pause() {
-    let env = Env::default();
-    let admin = Address::generate(&env);
-    let nft_contract = Address::generate(&env);
-    
-    let contract_id = env.register_contract(None, SeedRegistry);
-    let client = SeedRegistryClient::new(&env, &contract_id);
-    
-    client.initialize(&admin, &nft_contract);
+    let (_env, client, admin) = setup();
     
     assert!(!client.is_paused());
     
@@ -89,14 +128,7 @@ fn test_pause() {
 
 #[test]
 fn test_queries() {
-    let env = Env::default();
-    let admin = Address::generate(&env);
-    let nft_contract = Address::generate(&env);
-    
-    let contract_id = env.register_contract(None, SeedRegistry);
-    let client = SeedRegistryClient::new(&env, &contract_id);
-    
-    client.initialize(&admin, &nft_contract);
+    let (env, client, admin) = setup();
     
     let all_ids = client.get_all_seed_ids();
     assert_eq!(all_ids.len(), 0);
@@ -109,5 +141,140 @@ fn test_queries() {
     
     let by_creator = client.query_seeds_by_creator(&admin);
     assert_eq!(by_creator.len(), 0);
+    
+    let nft_ids = client.get_nft_ids_by_batch(&String::from_str(&env, "BATCH-001"));
+    assert_eq!(nft_ids.len(), 0);
+    
+    let nft_ids = client.get_nft_ids_by_seed_bank(&String::from_str(&env, "Bank-001"));
+    assert_eq!(nft_ids.len(), 0);
+    
+    let nft_ids = client.get_nft_ids_by_seed_id(&String::from_str(&env, "SEED-001"));
+    assert_eq!(nft_ids.len(), 0);
+    
+    assert!(client.get_seed_by_nft_id(&1).is_none());
 }
 
+
+#[test]
+fn test_regulator_role() {
+    let (env, client, admin) = setup();
+    let regulator = Address::generate(&env);
+    let user = Address::generate(&env);
+    
+    client.grant_role(&admin, &regulator, &ROLE_REGULATOR);
+    assert!(client.has_role(&regulator, &ROLE_REGULATOR));
+    
+    assert!(client.try_grant_role(&regulator, &user, &ROLE_CULTIVATOR).is_err());
+    assert!(client.try_freeze_seed(&user, &String::from_str(&env, "SEED-001"), &String::from_str(&env, "Audit")).is_err());
+    assert!(client.try_freeze_seed(&regulator, &String::from_str(&env, "SEED-404"), &String::from_str(&env, "Audit")).is_err());
+    assert!(!client.is_seed_frozen(&String::from_str(&env, "SEED-001")));
+}
+
+#[test]
+fn test_seed_bank_registry() {
+    let (env, client, admin) = setup();
+    let user = Address::generate(&env);
+    
+    let bank_id = String::from_str(&env, "Bank-001");
+    let bank = seed_bank(&env, "Bank-001");
+    assert!(client.try_approve_seed_bank(&user, &bank).is_err());
+    
+    client.approve_seed_bank(&admin, &bank);
+    assert_eq!(client.get_seed_bank(&bank_id), Some(bank.clone()));
+    assert_eq!(client.list_seed_banks(), Vec::from_array(&env, [bank_id.clone()]));
+    
+    client.add_seed_bank_certification(&admin, &bank_id, &BytesN::from_array(&env, &[2u8; 32]));
+    assert_eq!(client.get_seed_bank(&bank_id).unwrap().certifications.len(), 2);
+    let reapproved = SeedBank {
+        certifications: Vec::new(&env),
+        ..bank.clone()
+    };
+    assert!(client.try_approve_seed_bank(&admin, &reapproved).is_err());
+    assert_eq!(client.get_seed_bank(&bank_id).unwrap().certifications.len(), 2);
+    
+    client.set_seed_bank_status(&admin, &bank_id, &SeedBankStatus::Suspended);
+    assert_eq!(client.get_seed_bank(&bank_id).unwrap().status, SeedBankStatus::Suspended);
+    assert!(client.try_set_seed_bank_status(&admin, &String::from_str(&env, "Bank-404"), &SeedBankStatus::Active).is_err());
+    assert_eq!(client.get_seeds_by_seed_bank(&bank_id).len(), 0);
+}
+
+#[test]
+fn test_register_seed_requires_active_bank() {
+    let (env, client, admin) = setup();
+    client.approve_seed_bank(&admin, &seed_bank(&env, "Bank-001"));
+    client.approve_seed_bank(&admin, &seed_bank(&env, "Bank-002"));
+    
+    assert!(client.try_register_seed(
+        &String::from_str(&env, "SEED-001"),
+        &String::from_str(&env, "Indica"),
+        &String::from_str(&env, "BATCH-2024-001"),
+        &String::from_str(&env, "Colombia"),
+        &String::from_str(&env, "Bank-404"),
+        &None,
+        &None,
+        &false,
+    ).is_err());
+    
+    client.set_seed_bank_status(&admin, &String::from_str(&env, "Bank-002"), &SeedBankStatus::Suspended);
+    assert!(client.try_register_seed(
+        &String::from_str(&env, "SEED-001"),
+        &String::from_str(&env, "Indica"),
+        &String::from_str(&env, "BATCH-2024-001"),
+        &String::from_str(&env, "Colombia"),
+        &String::from_str(&env, "Bank-002"),
+        &None,
+        &None,
+        &false,
+    ).is_err());
+    assert_eq!(client.get_seed_count(), 0);
+    
+    client.set_seed_bank_status(&admin, &String::from_str(&env, "Bank-002"), &SeedBankStatus::Active);
+    let first = register_seed(&client, "SEED-001", "Bank-001");
+    let second = register_seed(&client, "SEED-002", "Bank-002");
+    let third = register_seed(&client, "SEED-003", "Bank-001");
+    
+    assert_eq!(
+        client.get_seeds_by_seed_bank(&String::from_str(&env, "Bank-001")),
+        Vec::from_array(&env, [String::from_str(&env, "SEED-001"), String::from_str(&env, "SEED-003")])
+    );
+    assert_eq!(
+        client.get_nft_ids_by_seed_bank(&String::from_str(&env, "Bank-001")),
+        Vec::from_array(&env, [first, third])
+    );
+    assert_eq!(
+        client.get_nft_ids_by_seed_bank(&String::from_str(&env, "Bank-002")),
+        Vec::from_array(&env, [second])
+    );
+}
+
+#[test]
+fn test_reindex_backfills_older_seeds() {
+    let (env, client, admin) = setup();
+    client.approve_seed_bank(&admin, &seed_bank(&env, "Bank-001"));
+    register_seed(&client, "SEED-001", "Bank-001");
+    
+    let legacy_id = String::from_str(&env, "SEED-000");
+    let legacy = SeedData {
+        id: legacy_id.clone(),
+        nft_id: 7,
+        ..client.get_seed(&String::from_str(&env, "SEED-001")).unwrap()
+    };
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("SEED_DATA"), legacy_id.clone()), &legacy);
+        Registry::add_seed_id(&env, &legacy_id);
+    });
+    let bank_id = String::from_str(&env, "Bank-001");
+    assert_eq!(client.get_seeds_by_seed_bank(&bank_id).len(), 1);
+    assert!(client.get_seed_by_nft_id(&7).is_none());
+    
+    let ids = client.get_all_seed_ids();
+    assert!(client.try_reindex_seeds(&Address::generate(&env), &ids).is_err());
+    client.reindex_seeds(&admin, &ids);
+    client.reindex_seeds(&admin, &ids);
+    
+    assert_eq!(client.get_seeds_by_seed_bank(&bank_id).len(), 2);
+    assert_eq!(client.get_nft_ids_by_seed_bank(&bank_id).len(), 2);
+    assert_eq!(client.get_seed_by_nft_id(&7).unwrap().id, legacy_id);
+}